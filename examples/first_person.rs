@@ -9,7 +9,7 @@ use bevy::{
 };
 use bevy_framepace::{FramepacePlugin, FramepaceSettings, Limiter};
 use bevy_mod_wanderlust::{
-    ControllerBundle, ControllerInput, ControllerPhysicsBundle, RapierPhysicsBundle,
+    ControllerBundle, ControllerInput, ControllerPhysicsBundle, RapierBackend, RapierPhysicsBundle,
     WanderlustPlugin,
 };
 use bevy_rapier3d::prelude::*;
@@ -53,7 +53,7 @@ fn main() {
         .add_systems(
             Update,
             (
-                movement_input.before(bevy_mod_wanderlust::movement_force),
+                movement_input.before(bevy_mod_wanderlust::movement_force::<RapierBackend>),
                 mouse_look,
                 toggle_cursor_lock,
             ),
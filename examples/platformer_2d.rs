@@ -0,0 +1,113 @@
+//! A simple example of setting up a 2D side-scrolling platformer player.
+//!
+//! Run with `cargo run --example platformer_2d --no-default-features --features rapier2d`.
+
+use bevy::prelude::*;
+use bevy_mod_wanderlust::{
+    Controller, ControllerInput, ControllerPhysicsBundle, Jump, Movement, Rapier2dBackend,
+    Rapier2dPhysicsBundle, WanderlustPlugin,
+};
+use bevy_rapier2d::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(1.0),
+            WanderlustPlugin::<Rapier2dBackend>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            movement_input.before(bevy_mod_wanderlust::movement_force::<Rapier2dBackend>),
+        )
+        .run()
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+struct PlayerBody;
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        projection: OrthographicProjection {
+            scale: 0.02,
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn((
+        Controller {
+            movement: Movement {
+                max_speed: 8.0,
+                ..default()
+            },
+            jump: Jump {
+                jumps: 2,
+                ..default()
+            },
+            ..default()
+        },
+        ControllerInput::default(),
+        ControllerPhysicsBundle::default(),
+        Rapier2dPhysicsBundle {
+            locked_axes: LockedAxes::ROTATION_LOCKED,
+            ..default()
+        },
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::ORANGE,
+                custom_size: Some(Vec2::new(0.6, 1.1)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 2.0, 0.0),
+            ..default()
+        },
+        Name::from("Player"),
+        PlayerBody,
+    ));
+
+    let platforms = [
+        (Vec2::new(0.0, -1.0), Vec2::new(20.0, 1.0)),
+        (Vec2::new(4.0, 1.5), Vec2::new(3.0, 0.5)),
+        (Vec2::new(-4.0, 3.0), Vec2::new(3.0, 0.5)),
+        (Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.5)),
+    ];
+
+    for (position, size) in platforms {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::WHITE,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            },
+            Collider::cuboid(size.x / 2.0, size.y / 2.0),
+            Name::from("Platform"),
+        ));
+    }
+}
+
+fn movement_input(
+    mut body: Query<&mut ControllerInput, With<PlayerBody>>,
+    input: Res<Input<KeyCode>>,
+) {
+    let Ok(mut player_input) = body.get_single_mut() else {
+        return;
+    };
+
+    let mut dir = Vec3::ZERO;
+    if input.pressed(KeyCode::A) {
+        dir -= Vec3::X;
+    }
+    if input.pressed(KeyCode::D) {
+        dir += Vec3::X;
+    }
+
+    player_input.movement = dir;
+    player_input.jumping = input.pressed(KeyCode::Space);
+}
@@ -10,8 +10,8 @@ use bevy::{
 use bevy_framepace::*;
 use bevy_mod_wanderlust::{
     Controller, ControllerBundle, ControllerInput, ControllerPhysicsBundle, Float, GroundCaster,
-    Jump, Movement, RapierPhysicsBundle, Spring, SpringStrength, Strength, Upright,
-    WanderlustPlugin,
+    Jump, Movement, MovementProfile, RapierBackend, RapierPhysicsBundle, Spring, SpringStrength,
+    Strength, Upright, WanderlustPlugin,
 };
 use bevy_rapier3d::prelude::*;
 use std::f32::consts::{FRAC_2_PI, PI};
@@ -92,7 +92,7 @@ fn main() {
         .add_systems(
             Update,
             (
-                movement_input.before(bevy_mod_wanderlust::movement_force),
+                movement_input.before(bevy_mod_wanderlust::movement_force::<RapierBackend>),
                 toggle_cursor_lock,
                 oscillating,
                 controlled_platform,
@@ -162,7 +162,8 @@ pub fn player(
                 },
                 controller: Controller {
                     movement: Movement {
-                        acceleration: Strength::Scaled(5.0),
+                        ground: MovementProfile::from_acceleration(Strength::Scaled(5.0)),
+                        air: MovementProfile::from_acceleration(Strength::Scaled(5.0)),
                         max_speed: 5.0,
                         //slip_force_scale: Vec3::splat(0.95),
                         ..default()
@@ -0,0 +1,715 @@
+use crate::{
+    backend::*,
+    controller::{CastResult, CrouchShapes},
+    physics::*,
+};
+use bevy::{
+    ecs::{schedule::ScheduleLabel, system::SystemParam},
+    prelude::*,
+};
+
+/// Shape of an [`AnalyticCollider`].
+#[derive(Debug, Clone, Copy, Reflect)]
+pub enum AnalyticShape {
+    /// Sphere centered on the entity.
+    Ball {
+        /// Radius of the sphere.
+        radius: f32,
+    },
+    /// Capsule around the segment `a`-`b`, in the entity's local space.
+    Capsule {
+        /// Start of the segment.
+        a: Vec3,
+        /// End of the segment.
+        b: Vec3,
+        /// Radius around the segment.
+        radius: f32,
+    },
+    /// Box centered on the entity.
+    Cuboid {
+        /// Half of the size of the box along each local axis.
+        half_extents: Vec3,
+    },
+    /// Infinite plane through the entity's origin. Everything behind the plane is solid.
+    ///
+    /// Rotate the entity to make a slope.
+    Plane {
+        /// Local normal of the plane.
+        normal: Vec3,
+    },
+}
+
+impl AnalyticShape {
+    /// Points/radius pairs making up a swept shape, in local space.
+    ///
+    /// Casts and contacts treat the shape as a set of spheres, which is exact for
+    /// balls and accurate enough for capsules standing on simple geometry.
+    fn spheres(&self) -> Option<([Vec3; 3], f32)> {
+        match *self {
+            Self::Ball { radius } => Some(([Vec3::ZERO; 3], radius)),
+            Self::Capsule { a, b, radius } => Some(([a, (a + b) / 2.0, b], radius)),
+            Self::Cuboid { .. } | Self::Plane { .. } => None,
+        }
+    }
+
+    /// Signed distance from a world-space point to this shape placed at `global`,
+    /// along with the outward normal at the closest point.
+    fn distance(&self, global: &GlobalTransform, point: Vec3) -> (f32, Vec3) {
+        let (_, rotation, translation) = global.to_scale_rotation_translation();
+        let local = rotation.inverse() * (point - translation);
+        let (distance, normal) = match *self {
+            Self::Ball { radius } => (local.length() - radius, local.normalize_or_zero()),
+            Self::Capsule { a, b, radius } => {
+                let closest = closest_on_segment(a, b, local);
+                let offset = local - closest;
+                (offset.length() - radius, offset.normalize_or_zero())
+            }
+            Self::Cuboid { half_extents } => {
+                let q = local.abs() - half_extents;
+                if q.max_element() > 0.0 {
+                    let outside = q.max(Vec3::ZERO);
+                    let normal = (outside * local.signum()).normalize_or_zero();
+                    (outside.length(), normal)
+                } else {
+                    // Inside, push out through the nearest face.
+                    let axis = if q.x > q.y && q.x > q.z {
+                        Vec3::X
+                    } else if q.y > q.z {
+                        Vec3::Y
+                    } else {
+                        Vec3::Z
+                    };
+                    (q.max_element(), axis * local.signum())
+                }
+            }
+            Self::Plane { normal } => {
+                let normal = normal.normalize_or_zero();
+                (local.dot(normal), normal)
+            }
+        };
+
+        (distance, rotation * normal)
+    }
+
+    /// Cast a ray inflated by `radius` against this shape placed at `global`.
+    ///
+    /// Returns the time of impact and the surface normal.
+    fn cast(
+        &self,
+        global: &GlobalTransform,
+        origin: Vec3,
+        direction: Vec3,
+        radius: f32,
+        max_toi: f32,
+    ) -> Option<(f32, Vec3)> {
+        let (_, rotation, translation) = global.to_scale_rotation_translation();
+        let inverse = rotation.inverse();
+        let local_origin = inverse * (origin - translation);
+        let local_direction = inverse * direction;
+
+        let (toi, normal) = match *self {
+            Self::Ball {
+                radius: ball_radius,
+            } => ray_sphere(local_origin, local_direction, ball_radius + radius)?,
+            Self::Capsule {
+                a,
+                b,
+                radius: capsule_radius,
+            } => {
+                // Approximate with the spheres at the ends and middle of the capsule.
+                [a, (a + b) / 2.0, b]
+                    .into_iter()
+                    .filter_map(|center| {
+                        ray_sphere(
+                            local_origin - center,
+                            local_direction,
+                            capsule_radius + radius,
+                        )
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0))?
+            }
+            Self::Cuboid { half_extents } => ray_box(
+                local_origin,
+                local_direction,
+                half_extents + Vec3::splat(radius),
+            )?,
+            Self::Plane { normal } => {
+                let normal = normal.normalize_or_zero();
+                let distance = local_origin.dot(normal) - radius;
+                let approach = -local_direction.dot(normal);
+                if distance < 0.0 || approach <= 0.0 {
+                    return None;
+                }
+                (distance / approach, normal)
+            }
+        };
+
+        if toi < 0.0 || toi > max_toi {
+            return None;
+        }
+
+        Some((toi, rotation * normal))
+    }
+}
+
+fn ray_sphere(origin: Vec3, direction: Vec3, radius: f32) -> Option<(f32, Vec3)> {
+    let b = origin.dot(direction);
+    let c = origin.length_squared() - radius * radius;
+    if c < 0.0 {
+        // Starting inside.
+        return Some((0.0, origin.normalize_or_zero()));
+    }
+    let discriminant = b * b - c;
+    if b > 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let toi = -b - discriminant.sqrt();
+    Some((toi, (origin + direction * toi).normalize_or_zero()))
+}
+
+fn ray_box(origin: Vec3, direction: Vec3, half_extents: Vec3) -> Option<(f32, Vec3)> {
+    let mut enter = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vec3::ZERO;
+
+    for axis in 0..3 {
+        let (o, d, h) = (origin[axis], direction[axis], half_extents[axis]);
+        if d.abs() <= f32::EPSILON {
+            if o.abs() > h {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (-h - o) / d;
+        let t2 = (h - o) / d;
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        if near > enter {
+            enter = near;
+            normal = Vec3::ZERO;
+            normal[axis] = -d.signum();
+        }
+        exit = exit.min(far);
+    }
+
+    if enter > exit || exit < 0.0 {
+        return None;
+    }
+
+    Some((enter.max(0.0), normal))
+}
+
+/// A collider in the analytic world.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct AnalyticCollider {
+    /// Shape of the collider.
+    pub shape: AnalyticShape,
+    /// Friction coefficient of the collider.
+    pub friction: f32,
+    /// Sensors are ignored by casts and never collide.
+    pub sensor: bool,
+}
+
+impl Default for AnalyticCollider {
+    fn default() -> Self {
+        Self {
+            shape: AnalyticShape::Ball { radius: 0.5 },
+            friction: 0.5,
+            sensor: false,
+        }
+    }
+}
+
+/// A moving body in the analytic world. Colliders without a body are static.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct AnalyticBody {
+    /// Kinematic bodies move with their velocity but ignore impulses and collisions,
+    /// which is useful for moving platforms.
+    pub kinematic: bool,
+    /// Mass of the body.
+    pub mass: f32,
+    /// Principal rotational inertia of the body.
+    pub inertia: Vec3,
+    /// Linear velocity of the body.
+    pub linear_velocity: Vec3,
+    /// Angular velocity of the body.
+    pub angular_velocity: Vec3,
+    /// Lock rotation of the body.
+    pub lock_rotation: bool,
+    /// Was the body touching another collider after the last step.
+    pub in_contact: bool,
+}
+
+impl Default for AnalyticBody {
+    fn default() -> Self {
+        Self {
+            kinematic: false,
+            mass: 1.0,
+            inertia: Vec3::splat(0.1),
+            linear_velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+            lock_rotation: false,
+            in_contact: false,
+        }
+    }
+}
+
+/// Impulse to apply to an [`AnalyticBody`] on the next step.
+#[derive(Component, Debug, Clone, Copy, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct AnalyticImpulse {
+    /// Linear impulse.
+    pub linear: Vec3,
+    /// Angular impulse.
+    pub angular: Vec3,
+}
+
+/// Contains common physics settings for character controllers in the analytic world.
+#[derive(Bundle)]
+pub struct AnalyticPhysicsBundle {
+    /// See [`AnalyticCollider`].
+    pub collider: AnalyticCollider,
+    /// See [`AnalyticBody`].
+    pub body: AnalyticBody,
+    /// See [`AnalyticImpulse`].
+    pub impulse: AnalyticImpulse,
+}
+
+impl Default for AnalyticPhysicsBundle {
+    fn default() -> Self {
+        Self {
+            collider: AnalyticCollider {
+                shape: AnalyticShape::Capsule {
+                    a: Vec3::new(0.0, 0.0, 0.0),
+                    b: Vec3::new(0.0, 0.5, 0.0),
+                    radius: 0.3,
+                },
+                friction: 0.0,
+                sensor: false,
+            },
+            // Mass of the capsule at unit density, so controllers tuned for Rapier
+            // behave the same here.
+            body: AnalyticBody {
+                mass: 0.2545,
+                inertia: Vec3::new(0.0198, 0.0115, 0.0198),
+                ..default()
+            },
+            impulse: default(),
+        }
+    }
+}
+
+impl Default for CrouchShapes<AnalyticShape> {
+    /// The capsule from [`AnalyticPhysicsBundle`], and a shorter one for crouching.
+    fn default() -> Self {
+        Self {
+            standing: AnalyticShape::Capsule {
+                a: Vec3::new(0.0, 0.0, 0.0),
+                b: Vec3::new(0.0, 0.5, 0.0),
+                radius: 0.3,
+            },
+            crouching: AnalyticShape::Capsule {
+                a: Vec3::new(0.0, 0.0, 0.0),
+                b: Vec3::new(0.0, 0.1, 0.0),
+                radius: 0.3,
+            },
+        }
+    }
+}
+
+/// Settings for the analytic world.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct AnalyticConfig {
+    /// Fixed timestep of every step, regardless of frame time.
+    pub dt: f32,
+}
+
+impl Default for AnalyticConfig {
+    fn default() -> Self {
+        Self { dt: 1.0 / 60.0 }
+    }
+}
+
+/// System set the analytic world is stepped in.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnalyticStep;
+
+/// A minimal, deterministic physics world made of analytic shapes.
+///
+/// This has no dependencies on a physics engine or renderer, so the controller can be
+/// simulated headlessly, e.g. in tests with `MinimalPlugins`. Only the shapes in
+/// [`AnalyticShape`] are supported and dynamic bodies are resolved as spheres.
+pub struct AnalyticPhysicsPlugin {
+    schedule: Box<dyn ScheduleLabel>,
+}
+
+impl AnalyticPhysicsPlugin {
+    /// Steps the world in the provided schedule rather than `PostUpdate`.
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = Box::new(schedule);
+        self
+    }
+}
+
+impl Default for AnalyticPhysicsPlugin {
+    fn default() -> Self {
+        Self {
+            schedule: Box::new(PostUpdate),
+        }
+    }
+}
+
+impl Plugin for AnalyticPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AnalyticCollider>()
+            .register_type::<AnalyticBody>()
+            .register_type::<AnalyticImpulse>()
+            .register_type::<AnalyticConfig>()
+            .init_resource::<AnalyticConfig>()
+            .add_systems(
+                self.schedule.clone(),
+                step_analytic_world.in_set(AnalyticStep),
+            );
+    }
+}
+
+/// Integrate bodies and resolve collisions in the analytic world.
+pub fn step_analytic_world(
+    config: Res<AnalyticConfig>,
+    mut bodies: Query<(
+        Entity,
+        &mut AnalyticBody,
+        Option<&mut AnalyticImpulse>,
+        &mut Transform,
+        &mut GlobalTransform,
+    )>,
+    colliders: Query<(Entity, &AnalyticCollider, &GlobalTransform), Without<AnalyticBody>>,
+    body_colliders: Query<&AnalyticCollider, With<AnalyticBody>>,
+) {
+    let dt = config.dt;
+
+    for (_, mut body, impulse, mut transform, mut global) in &mut bodies {
+        if let Some(mut impulse) = impulse {
+            if !body.kinematic {
+                let mass = body.mass.max(f32::EPSILON);
+                let inertia = body.inertia.max(Vec3::splat(f32::EPSILON));
+                body.linear_velocity += impulse.linear / mass;
+                body.angular_velocity += impulse.angular / inertia;
+            }
+            *impulse = AnalyticImpulse::default();
+        }
+
+        if body.lock_rotation {
+            body.angular_velocity = Vec3::ZERO;
+        }
+
+        transform.translation += body.linear_velocity * dt;
+        transform.rotation =
+            (Quat::from_scaled_axis(body.angular_velocity * dt) * transform.rotation).normalize();
+        *global = GlobalTransform::from(*transform);
+    }
+
+    // Kinematic bodies act as obstacles to dynamic bodies.
+    let kinematic = bodies
+        .iter()
+        .filter(|(_, body, ..)| body.kinematic)
+        .filter_map(|(entity, _, _, _, global)| {
+            body_colliders
+                .get(entity)
+                .ok()
+                .map(|collider| (entity, collider.clone(), *global))
+        })
+        .collect::<Vec<_>>();
+
+    for (entity, mut body, _, mut transform, mut global) in &mut bodies {
+        body.in_contact = false;
+        if body.kinematic {
+            continue;
+        }
+        let Ok(collider) = body_colliders.get(entity) else {
+            continue;
+        };
+        let Some((points, radius)) = collider.shape.spheres() else {
+            continue;
+        };
+
+        let obstacles = colliders
+            .iter()
+            .map(|(entity, collider, global)| (entity, collider.clone(), *global))
+            .chain(kinematic.iter().cloned())
+            .filter(|(other, collider, _)| *other != entity && !collider.sensor);
+
+        for (_, obstacle, obstacle_global) in obstacles {
+            for point in points {
+                let world_point = global.transform_point(point);
+                let (distance, normal) = obstacle.shape.distance(&obstacle_global, world_point);
+                let penetration = radius - distance;
+                if penetration < -CONTACT_SLOP {
+                    continue;
+                }
+
+                body.in_contact = true;
+                if penetration > 0.0 {
+                    transform.translation += normal * penetration;
+                    *global = GlobalTransform::from(*transform);
+                }
+
+                let into = body.linear_velocity.dot(normal);
+                if into < 0.0 {
+                    body.linear_velocity -= normal * into;
+                }
+            }
+        }
+    }
+}
+
+/// Distance within which two colliders are considered touching.
+const CONTACT_SLOP: f32 = 0.01;
+
+/// [`WanderlustBackend`] implementation for the analytic world of [`AnalyticPhysicsPlugin`].
+pub struct AnalyticBackend;
+
+/// Read-only access to the analytic world used by [`AnalyticBackend`].
+#[derive(SystemParam)]
+pub struct AnalyticSpatialQuery<'w, 's> {
+    /// Settings of the analytic world.
+    pub config: Res<'w, AnalyticConfig>,
+    /// Colliders in the world.
+    pub colliders: Query<'w, 's, (Entity, &'static AnalyticCollider, &'static GlobalTransform)>,
+    /// Bodies in the world.
+    pub bodies: Query<'w, 's, &'static AnalyticBody>,
+}
+
+impl AnalyticSpatialQuery<'_, '_> {
+    fn cast(
+        &self,
+        spheres: ([Vec3; 3], f32),
+        position: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)> {
+        let (points, radius) = spheres;
+        let direction = direction.normalize_or_zero();
+
+        let mut closest: Option<(Entity, CastResult)> = None;
+        for (entity, collider, global) in &self.colliders {
+            if !filter.test(entity, collider.sensor) {
+                continue;
+            }
+
+            for point in points {
+                let origin = position + rotation * point;
+                let Some((toi, normal)) = collider
+                    .shape
+                    .cast(global, origin, direction, radius, max_toi)
+                else {
+                    continue;
+                };
+
+                let closer = match closest {
+                    Some((_, cast)) => toi < cast.toi,
+                    None => true,
+                };
+                if closer {
+                    closest = Some((
+                        entity,
+                        CastResult {
+                            toi,
+                            normal,
+                            point: origin + direction * toi - normal * radius,
+                        },
+                    ));
+                }
+            }
+        }
+
+        closest
+    }
+}
+
+impl WanderlustBackend for AnalyticBackend {
+    type SpatialQuery = AnalyticSpatialQuery<'static, 'static>;
+    type Impulses = Query<'static, 'static, &'static mut AnalyticImpulse>;
+    type Shape = AnalyticShape;
+    type PhysicsSet = AnalyticStep;
+
+    fn physics_set() -> AnalyticStep {
+        AnalyticStep
+    }
+
+    fn dt(query: &AnalyticSpatialQuery) -> f32 {
+        query.config.dt
+    }
+
+    fn collider_shape(query: &AnalyticSpatialQuery, entity: Entity) -> Option<AnalyticShape> {
+        query
+            .colliders
+            .get(entity)
+            .ok()
+            .map(|(_, collider, _)| collider.shape)
+    }
+
+    fn set_collider_shape(commands: &mut Commands, entity: Entity, shape: AnalyticShape) {
+        commands.add(move |world: &mut World| {
+            if let Some(mut collider) = world.get_mut::<AnalyticCollider>(entity) {
+                collider.shape = shape;
+            }
+        });
+    }
+
+    fn cast_shape(
+        query: &AnalyticSpatialQuery,
+        shape: &AnalyticShape,
+        position: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)> {
+        let cast = query.cast(
+            shape.spheres()?,
+            position,
+            rotation,
+            direction,
+            max_toi,
+            filter,
+        )?;
+        if cast.1.toi <= f32::EPSILON {
+            return None;
+        }
+        Some(cast)
+    }
+
+    fn cast_ray(
+        query: &AnalyticSpatialQuery,
+        origin: Vec3,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)> {
+        query.cast(
+            ([Vec3::ZERO; 3], 0.0),
+            origin,
+            Quat::IDENTITY,
+            direction,
+            max_toi,
+            filter,
+        )
+    }
+
+    fn shape_extent(shape: &AnalyticShape, direction: Vec3) -> f32 {
+        let direction = direction.normalize_or_zero();
+        match *shape {
+            AnalyticShape::Ball { radius } => radius,
+            AnalyticShape::Capsule { a, b, radius } => {
+                a.dot(direction).max(b.dot(direction)).max(0.0) + radius
+            }
+            AnalyticShape::Cuboid { half_extents } => {
+                let scale = half_extents / direction.abs().max(Vec3::splat(f32::EPSILON));
+                scale.min_element()
+            }
+            AnalyticShape::Plane { .. } => 0.0,
+        }
+    }
+
+    fn contact_manifolds(
+        query: &AnalyticSpatialQuery,
+        shape: &AnalyticShape,
+        position: Vec3,
+        rotation: Quat,
+        filter: CastFilter,
+    ) -> Vec<(Entity, Vec3)> {
+        let Some((points, radius)) = shape.spheres() else {
+            return Vec::new();
+        };
+
+        let mut manifolds = Vec::new();
+        for (entity, collider, global) in &query.colliders {
+            if !filter.test(entity, collider.sensor) {
+                continue;
+            }
+
+            for point in points {
+                let (distance, normal) =
+                    collider.shape.distance(global, position + rotation * point);
+                if distance < radius {
+                    manifolds.push((entity, normal));
+                    break;
+                }
+            }
+        }
+
+        manifolds
+    }
+
+    fn point_intersections(
+        query: &AnalyticSpatialQuery,
+        point: Vec3,
+        filter: CastFilter,
+    ) -> Vec<Entity> {
+        query
+            .colliders
+            .iter()
+            .filter(|(entity, collider, _)| filter.test(*entity, collider.sensor))
+            .filter(|(_, collider, global)| collider.shape.distance(global, point).0 <= 0.0)
+            .map(|(entity, _, _)| entity)
+            .collect()
+    }
+
+    fn in_contact(query: &AnalyticSpatialQuery, entity: Entity) -> bool {
+        query.bodies.get(entity).is_ok_and(|body| body.in_contact)
+    }
+
+    fn collider_body(_: &AnalyticSpatialQuery, collider: Entity) -> Entity {
+        collider
+    }
+
+    fn velocity(query: &AnalyticSpatialQuery, entity: Entity) -> ControllerVelocity {
+        query
+            .bodies
+            .get(entity)
+            .map(|body| ControllerVelocity {
+                linear: body.linear_velocity,
+                angular: body.angular_velocity,
+            })
+            .unwrap_or_default()
+    }
+
+    fn mass(query: &AnalyticSpatialQuery, entity: Entity) -> ControllerMass {
+        query
+            .bodies
+            .get(entity)
+            .map(|body| ControllerMass {
+                mass: body.mass,
+                inertia: body.inertia,
+                com: Vec3::ZERO,
+            })
+            .unwrap_or_default()
+    }
+
+    fn friction(query: &AnalyticSpatialQuery, entity: Entity) -> f32 {
+        query
+            .colliders
+            .get(entity)
+            .map_or(AnalyticCollider::default().friction, |(_, collider, _)| {
+                collider.friction
+            })
+    }
+
+    fn apply_impulse(
+        impulses: &mut Query<&mut AnalyticImpulse>,
+        entity: Entity,
+        linear: Vec3,
+        angular: Vec3,
+    ) {
+        if let Ok(mut impulse) = impulses.get_mut(entity) {
+            impulse.linear += linear;
+            impulse.angular += angular;
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{controller::CastResult, physics::*};
+use bevy::{
+    ecs::{
+        schedule::SystemSet,
+        system::{SystemParam, SystemParamItem},
+    },
+    prelude::*,
+};
+
+/// Filter for which colliders should be considered by a cast.
+#[derive(Clone, Copy, Default)]
+pub struct CastFilter<'a> {
+    /// Ignore sensor colliders.
+    pub exclude_sensors: bool,
+    /// Only consider colliders for which this returns `true`.
+    pub predicate: Option<&'a dyn Fn(Entity) -> bool>,
+}
+
+impl<'a> CastFilter<'a> {
+    /// Filter that considers every collider.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ignore sensor colliders.
+    pub fn exclude_sensors(mut self) -> Self {
+        self.exclude_sensors = true;
+        self
+    }
+
+    /// Only consider colliders for which `predicate` returns `true`.
+    pub fn predicate(mut self, predicate: &'a dyn Fn(Entity) -> bool) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    /// Does this filter let the entity through?
+    pub fn test(&self, entity: Entity, sensor: bool) -> bool {
+        if self.exclude_sensors && sensor {
+            return false;
+        }
+
+        match self.predicate {
+            Some(predicate) => predicate(entity),
+            None => true,
+        }
+    }
+}
+
+/// A physics engine that the controller systems can run on top of.
+///
+/// Every controller system is generic over the backend, so the same controller
+/// logic can be used regardless of which physics engine moves the bodies.
+pub trait WanderlustBackend: Send + Sync + 'static {
+    /// Access to the physics world for casts and body lookups.
+    type SpatialQuery: SystemParam;
+    /// Access needed to apply impulses to bodies.
+    type Impulses: SystemParam;
+    /// Shape used for shape-casting.
+    type Shape: Clone + Send + Sync + 'static;
+    /// System set the controller systems must run before so forces are picked up
+    /// by the physics step.
+    type PhysicsSet: SystemSet + Clone;
+
+    /// The system set the controller systems are ordered before.
+    fn physics_set() -> Self::PhysicsSet;
+
+    /// Fixed timestep of the physics simulation.
+    fn dt(query: &BackendQuery<Self>) -> f32;
+
+    /// The collider shape attached to an entity.
+    fn collider_shape(query: &BackendQuery<Self>, entity: Entity) -> Option<Self::Shape>;
+
+    /// Replace the collider shape attached to an entity.
+    ///
+    /// This goes through `commands`, so the new shape is only seen once they are applied.
+    fn set_collider_shape(commands: &mut Commands, entity: Entity, shape: Self::Shape);
+
+    /// Cast a shape and return the first hit.
+    fn cast_shape(
+        query: &BackendQuery<Self>,
+        shape: &Self::Shape,
+        position: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)>;
+
+    /// Cast a ray and return the first hit.
+    fn cast_ray(
+        query: &BackendQuery<Self>,
+        origin: Vec3,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)>;
+
+    /// Distance from the origin of a shape to its surface along `direction`.
+    fn shape_extent(shape: &Self::Shape, direction: Vec3) -> f32;
+
+    /// Contacts between a shape and the world.
+    ///
+    /// Returns the entity touched and the world-space contact normal pointing away from that entity.
+    fn contact_manifolds(
+        query: &BackendQuery<Self>,
+        shape: &Self::Shape,
+        position: Vec3,
+        rotation: Quat,
+        filter: CastFilter,
+    ) -> Vec<(Entity, Vec3)>;
+
+    /// Colliders, including sensors unless filtered out, containing a world-space point.
+    fn point_intersections(
+        query: &BackendQuery<Self>,
+        point: Vec3,
+        filter: CastFilter,
+    ) -> Vec<Entity>;
+
+    /// Is the entity currently touching anything?
+    fn in_contact(query: &BackendQuery<Self>, entity: Entity) -> bool;
+
+    /// The body a collider is attached to, or the collider itself if it has no parent body.
+    fn collider_body(query: &BackendQuery<Self>, collider: Entity) -> Entity;
+
+    /// Velocity of a body, zero if it has none.
+    fn velocity(query: &BackendQuery<Self>, entity: Entity) -> ControllerVelocity;
+
+    /// Mass properties of a body, default if it has none.
+    fn mass(query: &BackendQuery<Self>, entity: Entity) -> ControllerMass;
+
+    /// Friction coefficient of a collider.
+    fn friction(query: &BackendQuery<Self>, entity: Entity) -> f32;
+
+    /// Add a linear and angular impulse to a body.
+    fn apply_impulse(
+        impulses: &mut SystemParamItem<Self::Impulses>,
+        entity: Entity,
+        linear: Vec3,
+        angular: Vec3,
+    );
+}
+
+/// Shorthand for the physics world access of a backend.
+pub type BackendQuery<'w, 's, B> = SystemParamItem<'w, 's, <B as WanderlustBackend>::SpatialQuery>;
@@ -1,4 +1,10 @@
-use crate::{Controller, ControllerInput, ControllerPhysicsBundle, RapierPhysicsBundle};
+#[cfg(feature = "rapier2d")]
+use crate::Rapier2dPhysicsBundle;
+#[cfg(feature = "rapier")]
+use crate::RapierPhysicsBundle;
+#[cfg(feature = "xpbd")]
+use crate::XpbdPhysicsBundle;
+use crate::{Controller, ControllerInput, ControllerPhysicsBundle};
 
 use bevy::prelude::*;
 
@@ -16,6 +22,12 @@ pub struct ControllerBundle {
     #[cfg(feature = "rapier")]
     /// See [`RapierPhysicsBundle`]
     pub rapier_physics: RapierPhysicsBundle,
+    #[cfg(feature = "rapier2d")]
+    /// See [`Rapier2dPhysicsBundle`]
+    pub rapier2d_physics: Rapier2dPhysicsBundle,
+    #[cfg(feature = "xpbd")]
+    /// See [`XpbdPhysicsBundle`]
+    pub xpbd_physics: XpbdPhysicsBundle,
     /// See [`Transform`]
     pub transform: Transform,
     /// See [`GlobalTransform`]
@@ -34,6 +46,10 @@ impl Default for ControllerBundle {
             physics: default(),
             #[cfg(feature = "rapier")]
             rapier_physics: default(),
+            #[cfg(feature = "rapier2d")]
+            rapier2d_physics: default(),
+            #[cfg(feature = "xpbd")]
+            xpbd_physics: default(),
             transform: default(),
             global_transform: default(),
             visibility: default(),
@@ -0,0 +1,115 @@
+use crate::controller::*;
+
+/// Lets the controller crouch while [`ControllerInput::crouching`] is held.
+///
+/// Crouching swaps the collider for [`CrouchShapes::crouching`], lowers
+/// [`Float::distance`] and [`Movement::max_speed`], and restores them when standing
+/// back up. Standing up is refused while there is no room above the controller.
+/// Changes made to either while crouched carry over when standing up, as only the
+/// difference crouching made is undone.
+///
+/// This is opt-in, add it along with [`CrouchShapes`] next to the [`Controller`] bundle
+/// to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct Crouch {
+    /// [`Float::distance`] while crouching.
+    pub float_distance: f32,
+    /// Multiplier for [`Movement::max_speed`] while crouching. This needs to be greater
+    /// than zero so it can be undone when standing up, smaller values are clamped.
+    pub speed_scale: f32,
+    /// Is the controller currently crouching.
+    pub crouching: bool,
+    /// Is the controller trying to stand up but blocked by a ceiling.
+    pub blocked: bool,
+    /// How much crouching lowered [`Float::distance`], added back when standing up.
+    pub float_offset: f32,
+    /// Multiplier applied to [`Movement::max_speed`] when crouching, divided back out
+    /// when standing up.
+    pub applied_speed_scale: f32,
+}
+
+impl Default for Crouch {
+    fn default() -> Self {
+        Self {
+            float_distance: 0.45,
+            speed_scale: 0.5,
+            crouching: false,
+            blocked: false,
+            float_offset: 0.0,
+            applied_speed_scale: 1.0,
+        }
+    }
+}
+
+/// Collider shapes to swap between when crouching. See [`Crouch`].
+#[derive(Component, Clone)]
+pub struct CrouchShapes<S: Clone + Send + Sync + 'static> {
+    /// Collider used while standing, this should match the controller's collider.
+    pub standing: S,
+    /// Collider used while crouching.
+    pub crouching: S,
+}
+
+/// Crouch and stand up depending on input, as long as there is room to stand.
+#[allow(clippy::type_complexity)]
+pub fn crouch<B: WanderlustBackend>(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut Crouch,
+        &CrouchShapes<B::Shape>,
+        &mut Float,
+        &mut Movement,
+        &ControllerInput,
+        &GroundCaster,
+        &Gravity,
+    )>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+) {
+    for (entity, global, mut crouch, shapes, mut float, mut movement, input, caster, gravity) in
+        &mut query
+    {
+        if input.crouching && !crouch.crouching {
+            crouch.float_offset = float.distance - crouch.float_distance;
+            crouch.applied_speed_scale = crouch.speed_scale.max(f32::EPSILON);
+            float.distance = crouch.float_distance;
+            movement.max_speed *= crouch.applied_speed_scale;
+            B::set_collider_shape(&mut commands, entity, shapes.crouching.clone());
+            crouch.crouching = true;
+        } else if !input.crouching && crouch.crouching {
+            // Room needed above the crouched collider: the standing collider is taller, and
+            // floats higher.
+            let up_vector = gravity.up_vector;
+            let needed = B::shape_extent(&shapes.standing, up_vector)
+                - B::shape_extent(&shapes.crouching, up_vector)
+                + crouch.float_offset;
+
+            let predicate =
+                |collider| collider != entity && !caster.exclude_from_ground.contains(&collider);
+            let (_, rotation, position) = global.to_scale_rotation_translation();
+            // Start slightly below so a collider already touching the ceiling still hits it.
+            crouch.blocked = B::cast_shape(
+                &backend,
+                &shapes.crouching,
+                position - up_vector * FUDGE,
+                rotation,
+                up_vector,
+                needed.max(0.0) + FUDGE,
+                CastFilter::new().exclude_sensors().predicate(&predicate),
+            )
+            .is_some();
+            if crouch.blocked {
+                continue;
+            }
+
+            float.distance += crouch.float_offset;
+            movement.max_speed /= crouch.applied_speed_scale;
+            B::set_collider_shape(&mut commands, entity, shapes.standing.clone());
+            crouch.crouching = false;
+        } else {
+            crouch.blocked = false;
+        }
+    }
+}
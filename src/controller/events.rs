@@ -0,0 +1,200 @@
+use crate::controller::*;
+
+/// Sent when a controller starts a jump.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Jumped {
+    /// Controller that jumped.
+    pub entity: Entity,
+    /// Which jump this is since last touching the ground, starting at 1.
+    ///
+    /// For wall jumps this counts wall jumps instead.
+    pub jump_index: u32,
+    /// Wall the controller jumped off of, if this was a wall jump.
+    pub wall: Option<Entity>,
+}
+
+/// Sent when a controller becomes grounded, describing how hard it hit the ground.
+///
+/// See [`FallDamage`] for turning this into damage.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Landed {
+    /// Controller that landed.
+    pub entity: Entity,
+    /// Entity the controller landed on.
+    pub ground: Entity,
+    /// Velocity of the controller relative to the ground when it landed.
+    pub impact_velocity: Vec3,
+    /// Velocity along the up vector, relative to the ground's `point_velocity`,
+    /// when the controller landed. This is negative when falling onto the ground.
+    pub vertical_velocity: f32,
+    /// How far the controller fell from the highest point it reached since it was
+    /// last grounded.
+    pub fall_height: f32,
+}
+
+/// Sent when a controller stops being grounded.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct LeftGround {
+    /// Controller that left the ground.
+    pub entity: Entity,
+    /// Last entity the controller was standing on.
+    pub ground: Option<Entity>,
+    /// Did the controller leave the ground by jumping.
+    pub jumped: bool,
+}
+
+/// Sent when the viable ground entity below a controller changes.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct GroundEntityChanged {
+    /// Controller whose ground changed.
+    pub entity: Entity,
+    /// Previous ground entity.
+    pub previous: Option<Entity>,
+    /// Current ground entity.
+    pub ground: Option<Entity>,
+}
+
+/// Sent when a grounded controller starts standing on unstable ground, see
+/// [`GroundCaster::unstable_ground_angle`].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct StartedSlipping {
+    /// Controller that started slipping.
+    pub entity: Entity,
+    /// Entity the controller is slipping on.
+    pub ground: Entity,
+}
+
+/// Sent when the coyote time after walking off the ground runs out without a jump.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CoyoteExpired {
+    /// Controller that can no longer coyote jump.
+    pub entity: Entity,
+}
+
+/// State from last frame, used to find transitions to send events for.
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct ControllerEventState {
+    /// Was the controller grounded.
+    pub grounded: bool,
+    /// Viable ground entity below the controller.
+    pub ground: Option<Entity>,
+    /// Was the controller standing on unstable ground.
+    pub slipping: bool,
+    /// Was coyote time running.
+    pub coyote: bool,
+    /// Position of the controller, `None` until the first update.
+    pub position: Option<Vec3>,
+    /// Height along the up vector above where the controller was last grounded.
+    pub height: f32,
+    /// Highest `height` reached since the controller was last grounded.
+    pub peak_height: f32,
+}
+
+/// Send events for changes in the controller's ground and jump state.
+#[allow(clippy::type_complexity)]
+pub fn controller_events(
+    mut query: Query<(
+        Entity,
+        &mut ControllerEventState,
+        &ViableGroundCast,
+        &Grounded,
+        &Jump,
+        &ControllerVelocity,
+        &GlobalTransform,
+        &Gravity,
+    )>,
+    mut landed: EventWriter<Landed>,
+    mut left_ground: EventWriter<LeftGround>,
+    mut ground_changed: EventWriter<GroundEntityChanged>,
+    mut started_slipping: EventWriter<StartedSlipping>,
+    mut coyote_expired: EventWriter<CoyoteExpired>,
+) {
+    for (entity, mut state, viable_ground, grounded, jump, velocity, global, gravity) in &mut query
+    {
+        let grounded = **grounded;
+        let current = viable_ground.current();
+        let ground = current.map(|ground| ground.entity);
+        let slipping = grounded && current.is_some_and(|ground| !ground.stable);
+        let coyote = jump.coyote_timer > 0.0;
+        let position = global.translation();
+
+        // Start from whatever the controller spawned as, rather than sending events
+        // for the difference to the default state.
+        let Some(previous) = state.position else {
+            *state = ControllerEventState {
+                grounded,
+                ground,
+                slipping,
+                coyote,
+                position: Some(position),
+                height: 0.0,
+                peak_height: 0.0,
+            };
+            continue;
+        };
+
+        // Accumulated along the current up vector, so neither where the controller is
+        // nor up vector changes mid-air skew the fall height.
+        let height = state.height + (position - previous).dot(gravity.up_vector);
+        let peak_height = state.peak_height.max(height);
+
+        if ground != state.ground {
+            ground_changed.send(GroundEntityChanged {
+                entity,
+                previous: state.ground,
+                ground,
+            });
+        }
+
+        match (state.grounded, grounded, current) {
+            (false, true, Some(current)) => {
+                let impact_velocity = velocity.linear - current.point_velocity;
+                landed.send(Landed {
+                    entity,
+                    ground: current.entity,
+                    impact_velocity,
+                    vertical_velocity: impact_velocity.dot(gravity.up_vector),
+                    fall_height: (peak_height - height).max(0.0),
+                });
+            }
+            (true, false, _) => {
+                left_ground.send(LeftGround {
+                    entity,
+                    ground: state.ground,
+                    jumped: jump.jumping() || jump.remaining_jumps < jump.jumps,
+                });
+            }
+            _ => {}
+        }
+
+        if slipping && !state.slipping {
+            if let Some(current) = current {
+                started_slipping.send(StartedSlipping {
+                    entity,
+                    ground: current.entity,
+                });
+            }
+        }
+
+        if state.coyote && !coyote && !grounded && jump.remaining_jumps == jump.jumps {
+            coyote_expired.send(CoyoteExpired { entity });
+        }
+
+        let (height, peak_height) = if grounded {
+            (0.0, 0.0)
+        } else {
+            (height, peak_height)
+        };
+
+        *state = ControllerEventState {
+            grounded,
+            ground,
+            slipping,
+            coyote,
+            position: Some(position),
+            height,
+            peak_height,
+        };
+    }
+}
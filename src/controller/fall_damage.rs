@@ -0,0 +1,82 @@
+use crate::controller::*;
+
+/// Turns hard landings into [`FallDamaged`] events.
+///
+/// Landing faster than [`FallDamage::min_speed`] deals damage, growing along a curve
+/// up to [`FallDamage::max_damage`] at [`FallDamage::max_speed`].
+///
+/// This is opt-in, add it next to the [`Controller`] bundle to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct FallDamage {
+    /// Landing speed, into the ground, below which no damage is dealt.
+    pub min_speed: f32,
+    /// Landing speed at which [`FallDamage::max_damage`] is dealt.
+    pub max_speed: f32,
+    /// Damage dealt when landing at exactly [`FallDamage::min_speed`].
+    pub min_damage: f32,
+    /// Damage dealt when landing at or above [`FallDamage::max_speed`].
+    pub max_damage: f32,
+    /// Shape of the curve between the minimum and maximum damage.
+    ///
+    /// `1.0` is linear, higher values make small falls more forgiving and big falls
+    /// ramp up quicker.
+    pub exponent: f32,
+}
+
+impl Default for FallDamage {
+    fn default() -> Self {
+        Self {
+            min_speed: 10.0,
+            max_speed: 25.0,
+            min_damage: 0.0,
+            max_damage: 100.0,
+            exponent: 1.0,
+        }
+    }
+}
+
+impl FallDamage {
+    /// Damage for landing at `speed` into the ground, `None` if the landing is harmless.
+    pub fn damage(&self, speed: f32) -> Option<f32> {
+        if speed <= self.min_speed {
+            return None;
+        }
+
+        let range = (self.max_speed - self.min_speed).max(f32::EPSILON);
+        let t = ((speed - self.min_speed) / range).clamp(0.0, 1.0);
+        Some(self.min_damage + (self.max_damage - self.min_damage) * t.powf(self.exponent))
+    }
+}
+
+/// Sent when a controller with [`FallDamage`] lands hard enough to be hurt.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FallDamaged {
+    /// Controller that was hurt.
+    pub entity: Entity,
+    /// Damage from the [`FallDamage`] curve.
+    pub damage: f32,
+    /// The landing that caused the damage.
+    pub landing: Landed,
+}
+
+/// Send [`FallDamaged`] for hard landings.
+pub fn fall_damage(
+    query: Query<&FallDamage>,
+    mut landed: EventReader<Landed>,
+    mut damaged: EventWriter<FallDamaged>,
+) {
+    for landing in landed.iter() {
+        let Ok(fall_damage) = query.get(landing.entity) else {
+            continue;
+        };
+
+        if let Some(damage) = fall_damage.damage(-landing.vertical_velocity) {
+            damaged.send(FallDamaged {
+                entity: landing.entity,
+                damage,
+                landing: *landing,
+            });
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::controller::*;
 
 /// How strong is the gravity for this controller.
-#[derive(Component, Reflect)]
+#[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component, Default)]
 pub struct Gravity {
     /// Acceleration in the `up_vector` direction due to gravity.
@@ -25,6 +25,83 @@ impl Default for Gravity {
     }
 }
 
+/// Scales gravity depending on how the controller is moving through the air, for
+/// snappier jump arcs.
+///
+/// With this, releasing jump early raises gravity instead of applying
+/// [`Jump::stop_force`]. This only applies while the controller isn't grounded.
+///
+/// This is opt-in, add it next to the [`Controller`] bundle to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct GravityModifiers {
+    /// Multiplier for gravity while moving down along the up vector.
+    pub fall_multiplier: f32,
+    /// Multiplier for gravity while still rising after jump was released.
+    pub release_multiplier: f32,
+    /// Multiplier for gravity while the speed along the up vector is below
+    /// `apex_threshold` after a jump, to hang at the top of it.
+    ///
+    /// Falls that didn't start with a jump, such as walking off a ledge, aren't slowed.
+    pub apex_multiplier: f32,
+    /// Speed along the up vector below which `apex_multiplier` applies.
+    pub apex_threshold: f32,
+}
+
+impl Default for GravityModifiers {
+    fn default() -> Self {
+        Self {
+            fall_multiplier: 2.0,
+            release_multiplier: 2.0,
+            apex_multiplier: 0.5,
+            apex_threshold: 1.0,
+        }
+    }
+}
+
+impl GravityModifiers {
+    /// Gravity multiplier for a controller moving at `vertical_speed` along the up
+    /// vector, `jumped` being whether it has jumped since leaving the ground and
+    /// `released` whether it is rising from a jump that was let go of.
+    pub fn multiplier(&self, vertical_speed: f32, jumped: bool, released: bool) -> f32 {
+        if vertical_speed > 0.0 && released {
+            self.release_multiplier
+        } else if jumped && vertical_speed.abs() < self.apex_threshold {
+            self.apex_multiplier
+        } else if vertical_speed < 0.0 {
+            self.fall_multiplier
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Slows the controller down while airborne and caps how fast it can fall.
+///
+/// This is applied on top of every other force, so nothing pushes the controller past
+/// `terminal_velocity`.
+///
+/// This is opt-in, add it next to the [`Controller`] bundle to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct AirDrag {
+    /// Fastest the controller can fall along [`Gravity::up_vector`], or `None` for no limit.
+    pub terminal_velocity: Option<f32>,
+    /// Quadratic drag coefficient while airborne, scaled by mass.
+    ///
+    /// The drag acceleration is `coefficient * speed²`, opposite to the velocity.
+    pub quadratic_drag: f32,
+}
+
+impl Default for AirDrag {
+    fn default() -> Self {
+        Self {
+            terminal_velocity: Some(50.0),
+            quadratic_drag: 0.0,
+        }
+    }
+}
+
 /// Calculated gravity force.
 #[derive(Component, Default, Reflect)]
 #[reflect(Component, Default)]
@@ -34,8 +111,29 @@ pub struct GravityForce {
 }
 
 /// Calculate gravity force.
-pub fn gravity_force(mut query: Query<(&mut GravityForce, &Gravity, &ControllerMass)>) {
-    for (mut force, gravity, mass) in &mut query {
-        force.linear = gravity.up_vector * mass.mass * gravity.acceleration;
+#[allow(clippy::type_complexity)]
+pub fn gravity_force(
+    mut query: Query<(
+        &mut GravityForce,
+        &Gravity,
+        &ControllerMass,
+        &ControllerVelocity,
+        &Grounded,
+        Option<&GravityModifiers>,
+        Option<(&Jump, &ControllerInput)>,
+    )>,
+) {
+    for (mut force, gravity, mass, velocity, grounded, modifiers, jump) in &mut query {
+        let multiplier = match modifiers {
+            Some(modifiers) if !**grounded => {
+                let jumped = jump
+                    .is_some_and(|(jump, _)| jump.jumping() || jump.remaining_jumps < jump.jumps);
+                let released = jumped && jump.is_some_and(|(_, input)| !input.jumping);
+                modifiers.multiplier(velocity.linear.dot(gravity.up_vector), jumped, released)
+            }
+            _ => 1.0,
+        };
+
+        force.linear = gravity.up_vector * mass.mass * gravity.acceleration * multiplier;
     }
 }
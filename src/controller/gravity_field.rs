@@ -0,0 +1,180 @@
+use crate::controller::*;
+
+/// A volume that changes the [`Gravity`] of controllers inside of it.
+///
+/// Add this to a sensor collider, the field applies to controllers with a
+/// [`GravityFieldReceiver`] whose origin is inside the collider.
+///
+/// When a controller is inside several fields, only the ones with the highest
+/// `priority` apply, and their pulls are blended by `weight`.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct GravityField {
+    /// Which way the field pulls.
+    pub shape: GravityFieldShape,
+    /// Acceleration along the up vector, like [`Gravity::acceleration`].
+    pub acceleration: f32,
+    /// Fields with a higher priority override fields with a lower one.
+    pub priority: i32,
+    /// How much this field counts when blended with other fields of the same priority.
+    pub weight: f32,
+}
+
+impl Default for GravityField {
+    fn default() -> Self {
+        Self {
+            shape: GravityFieldShape::default(),
+            acceleration: -9.817,
+            priority: 0,
+            weight: 1.0,
+        }
+    }
+}
+
+/// Which way a [`GravityField`] pulls. Positions and directions are local to the field.
+#[derive(Debug, Clone, Reflect)]
+pub enum GravityFieldShape {
+    /// Pulls in a single direction, the up vector is `up`.
+    Directional {
+        /// Up vector inside the field.
+        up: Vec3,
+    },
+    /// Pulls towards the field's origin, like a planetoid.
+    Point,
+    /// Pulls towards a line through the field's origin.
+    Cylinder {
+        /// Direction of the line.
+        axis: Vec3,
+    },
+    /// Pulls towards the closest point on a path, e.g. one sampled from a spline.
+    ///
+    /// The points are joined by straight segments.
+    Spline {
+        /// Points along the path.
+        points: Vec<Vec3>,
+    },
+}
+
+impl Default for GravityFieldShape {
+    fn default() -> Self {
+        Self::Directional { up: Vec3::Y }
+    }
+}
+
+impl GravityFieldShape {
+    /// Up vector at the world-space `point` for this shape placed at `global`.
+    pub fn up_vector(&self, global: &GlobalTransform, point: Vec3) -> Vec3 {
+        let (_, rotation, origin) = global.to_scale_rotation_translation();
+        let up = match self {
+            Self::Directional { up } => rotation * *up,
+            Self::Point => point - origin,
+            Self::Cylinder { axis } => (point - origin).reject_from(rotation * *axis),
+            Self::Spline { points } => {
+                let closest = points
+                    .windows(2)
+                    .map(|segment| {
+                        let a = global.transform_point(segment[0]);
+                        let b = global.transform_point(segment[1]);
+                        closest_on_segment(a, b, point)
+                    })
+                    .min_by(|a, b| {
+                        a.distance_squared(point)
+                            .total_cmp(&b.distance_squared(point))
+                    })
+                    .or_else(|| points.first().map(|first| global.transform_point(*first)))
+                    .unwrap_or(origin);
+                point - closest
+            }
+        };
+        up.normalize_or_zero()
+    }
+}
+
+/// Lets [`GravityField`]s set the controller's [`Gravity`].
+///
+/// This is opt-in, add it next to the [`Controller`] bundle to enable it. Without it
+/// [`Gravity`] is left alone.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct GravityFieldReceiver {
+    /// Gravity to use when outside of every field, or `None` to keep the gravity of the
+    /// last field the controller was in.
+    pub outside: Option<Gravity>,
+    /// Fields that currently apply to the controller.
+    pub fields: Vec<Entity>,
+}
+
+/// Set the [`Gravity`] of controllers from the [`GravityField`]s they are inside.
+pub fn apply_gravity_fields<B: WanderlustBackend>(
+    mut receivers: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut Gravity,
+        &mut GravityFieldReceiver,
+    )>,
+    fields: Query<(&GravityField, &GlobalTransform)>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+) {
+    for (entity, global, mut gravity, mut receiver) in &mut receivers {
+        let position = global.translation();
+
+        let field_entity = |collider: Entity| {
+            if fields.contains(collider) {
+                Some(collider)
+            } else {
+                let body = B::collider_body(&backend, collider);
+                fields.contains(body).then_some(body)
+            }
+        };
+        let predicate = |collider| collider != entity && field_entity(collider).is_some();
+        let inside: Vec<(Entity, i32)> =
+            B::point_intersections(&backend, position, CastFilter::new().predicate(&predicate))
+                .into_iter()
+                .filter_map(field_entity)
+                .filter_map(|field| Some((field, fields.get(field).ok()?.0.priority)))
+                .collect();
+
+        let Some(priority) = inside.iter().map(|(_, priority)| *priority).max() else {
+            receiver.fields.clear();
+            if let Some(outside) = &receiver.outside {
+                if gravity.up_vector != outside.up_vector
+                    || gravity.acceleration != outside.acceleration
+                {
+                    *gravity = outside.clone();
+                }
+            }
+            continue;
+        };
+
+        receiver.fields = inside
+            .into_iter()
+            .filter(|(_, field_priority)| *field_priority == priority)
+            .map(|(field, _)| field)
+            .collect();
+        receiver.fields.sort();
+        receiver.fields.dedup();
+
+        // Blend the accelerations as vectors so opposing fields cancel out.
+        let mut total_weight = 0.0;
+        let mut blended = Vec3::ZERO;
+        for (field, field_global) in fields.iter_many(&receiver.fields) {
+            let up_vector = field.shape.up_vector(field_global, position);
+            blended += up_vector * field.acceleration * field.weight;
+            total_weight += field.weight;
+        }
+        if total_weight <= 0.0 {
+            continue;
+        }
+        let blended = blended / total_weight;
+
+        // Leave gravity untouched while the fields stay the same, for change detection.
+        let up_vector = -blended.normalize_or_zero();
+        if up_vector != Vec3::ZERO && gravity.up_vector != up_vector {
+            gravity.up_vector = up_vector;
+        }
+        let acceleration = -blended.length();
+        if gravity.acceleration != acceleration {
+            gravity.acceleration = acceleration;
+        }
+    }
+}
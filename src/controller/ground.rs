@@ -1,13 +1,6 @@
 use crate::controller::*;
 use bevy::utils::HashSet;
-use bevy_rapier3d::{
-    na::Isometry3,
-    parry::{
-        bounding_volume::BoundingVolume,
-        query::{DefaultQueryDispatcher, PersistentQueryDispatcher},
-    },
-    rapier::geometry::ContactManifold,
-};
+use std::marker::PhantomData;
 
 /// How to detect if something below the controller is suitable
 /// for standing on.
@@ -23,11 +16,6 @@ pub struct GroundCaster {
     /// How long of a ray to cast to detect the ground. Setting this unnecessarily high will permanently count the player as grounded,
     /// and too low will allow the player to slip and become disconnected from the ground easily.
     pub cast_length: f32,
-    /// What shape of ray to cast. See [`Collider`] and [`RapierContext::cast_shape`](bevy_rapier::prelude::RapierContext).
-    ///
-    /// The default is the controller's base collider.
-    #[reflect(ignore)]
-    pub cast_collider: Option<Collider>,
     /// Set of entities that should be ignored when ground casting.
     pub exclude_from_ground: HashSet<Entity>,
 
@@ -49,7 +37,6 @@ impl Default for GroundCaster {
             skip_ground_check_override: false,
             cast_origin: Vec3::ZERO,
             cast_length: 1.05,
-            cast_collider: None,
             exclude_from_ground: default(),
             unstable_ground_angle: 45.0 * (std::f32::consts::PI / 180.0),
             max_ground_angle: 60.0 * (std::f32::consts::PI / 180.0),
@@ -57,6 +44,12 @@ impl Default for GroundCaster {
     }
 }
 
+/// What shape to cast when looking for the ground. See [`WanderlustBackend::cast_shape`].
+///
+/// Without this component the controller's own collider is used.
+#[derive(Component, Clone)]
+pub struct GroundCastShape<S: Clone + Send + Sync + 'static>(pub S);
+
 /// Information about the ground entity/where we are touching it.
 #[derive(Copy, Clone, Reflect)]
 pub struct Ground {
@@ -74,41 +67,33 @@ pub struct Ground {
     pub linear_velocity: Vec3,
     /// Linear velocity at the point of contact.
     pub point_velocity: Vec3,
+    /// Properties of the surface, from the collider or its body.
+    pub surface: SurfaceProperties,
 }
 
 impl Ground {
     /// Construct a `Ground` based on the results of `GroundCastParams`.
-    pub fn from_cast(
+    pub fn from_cast<B: WanderlustBackend>(
         entity: Entity,
         cast: CastResult,
         up_vector: Vec3,
         caster: &GroundCaster,
-        ctx: &RapierContext,
-        masses: &Query<&ReadMassProperties>,
-        velocities: &Query<&Velocity>,
+        backend: &BackendQuery<B>,
         globals: &Query<&GlobalTransform>,
+        surfaces: &Query<&SurfaceProperties>,
     ) -> Self {
-        let ground_entity = ctx.collider_parent(entity).unwrap_or(entity);
-
-        let mass = if let Ok(mass) = masses.get(ground_entity) {
-            (**mass).clone()
-        } else {
-            MassProperties::default()
-        };
+        let ground_entity = B::collider_body(backend, entity);
 
-        let local_com = mass.local_center_of_mass;
+        let local_com = B::mass(backend, ground_entity).com;
 
-        let ground_velocity = velocities
-            .get(ground_entity)
-            .copied()
-            .unwrap_or(Velocity::default());
+        let ground_velocity = B::velocity(backend, ground_entity);
 
         let global = globals
             .get(ground_entity)
             .unwrap_or(&GlobalTransform::IDENTITY);
         let com = global.transform_point(local_com);
         let point_velocity =
-            ground_velocity.linvel + ground_velocity.angvel.cross(cast.point - com);
+            ground_velocity.linear + ground_velocity.angular.cross(cast.point - com);
 
         let (stable, viable) = if cast.normal.length() > 0.0 {
             let viable = cast.viable(up_vector, caster.max_ground_angle);
@@ -123,9 +108,14 @@ impl Ground {
             cast: cast,
             stable: stable,
             viable: viable,
-            linear_velocity: ground_velocity.linvel,
-            angular_velocity: ground_velocity.angvel,
+            linear_velocity: ground_velocity.linear,
+            angular_velocity: ground_velocity.angular,
             point_velocity: point_velocity,
+            surface: surfaces
+                .get(entity)
+                .or_else(|_| surfaces.get(ground_entity))
+                .copied()
+                .unwrap_or_default(),
         }
     }
 }
@@ -217,7 +207,8 @@ pub struct GroundForce {
 }
 
 /// Performs groundcasting and updates controller state accordingly.
-pub fn find_ground(
+#[allow(clippy::type_complexity)]
+pub fn find_ground<B: WanderlustBackend>(
     time: Res<Time>,
     mut casters: Query<(
         Entity,
@@ -226,81 +217,81 @@ pub fn find_ground(
         &mut GroundCaster,
         &mut GroundCast,
         &mut ViableGroundCast,
+        Option<&GroundCastShape<B::Shape>>,
     )>,
 
-    velocities: Query<&Velocity>,
-    masses: Query<&ReadMassProperties>,
     globals: Query<&GlobalTransform>,
-    colliders: Query<&Collider>,
+    surfaces: Query<&SurfaceProperties>,
 
-    ctx: Res<RapierContext>,
+    backend: StaticSystemParam<B::SpatialQuery>,
     mut gizmos: Gizmos,
 ) {
-    let dt = ctx.integration_parameters.dt;
+    let dt = B::dt(&backend);
     if time.delta_seconds() == 0.0 {
         return;
     }
 
-    for (entity, tf, gravity, mut caster, mut ground, mut viable_ground) in &mut casters {
+    for (entity, tf, gravity, mut caster, mut ground, mut viable_ground, cast_shape) in &mut casters
+    {
         if caster.skip_ground_check_timer == 0.0 && !caster.skip_ground_check_override {
             let cast_position = tf.transform_point(caster.cast_origin);
             let cast_rotation = tf.to_scale_rotation_translation().1;
             let cast_direction = -gravity.up_vector;
-            let Ok(caster_collider) = colliders.get(entity) else {
+            let Some(shape) = cast_shape
+                .map(|shape| shape.0.clone())
+                .or_else(|| B::collider_shape(&backend, entity))
+            else {
                 continue;
             };
-            let shape = caster.cast_collider.as_ref().unwrap_or(caster_collider);
 
             let predicate =
                 |collider| collider != entity && !caster.exclude_from_ground.contains(&collider);
-            let filter = QueryFilter::new().exclude_sensors().predicate(&predicate);
+            let filter = CastFilter::new().exclude_sensors().predicate(&predicate);
 
-            let mut viable_params = GroundCastParams {
+            let mut viable_params = GroundCastParams::<B> {
                 position: cast_position,
                 rotation: cast_rotation,
                 direction: cast_direction,
                 shape: &shape,
                 max_toi: caster.cast_length,
                 filter: filter,
+                backend: PhantomData,
             };
 
             let mut any_params = viable_params.clone();
 
             let next_viable_ground = viable_params
                 .viable_cast_iters(
-                    &*ctx,
-                    &globals,
+                    &backend,
                     caster.max_ground_angle,
                     gravity.up_vector,
                     5,
                     &mut gizmos,
                 )
                 .map(|(entity, cast)| {
-                    Ground::from_cast(
+                    Ground::from_cast::<B>(
                         entity,
                         cast,
                         gravity.up_vector,
                         &*caster,
-                        &*ctx,
-                        &masses,
-                        &velocities,
+                        &backend,
                         &globals,
+                        &surfaces,
                     )
                 });
             viable_ground.update(next_viable_ground);
 
             let next_ground = any_params
-                .cast_iters(&*ctx, &globals, gravity.up_vector, 5, &mut gizmos)
+                .cast_iters(&backend, gravity.up_vector, 5, &mut gizmos)
                 .map(|(entity, cast)| {
-                    Ground::from_cast(
+                    Ground::from_cast::<B>(
                         entity,
                         cast,
                         gravity.up_vector,
                         &*caster,
-                        &*ctx,
-                        &masses,
-                        &velocities,
+                        &backend,
                         &globals,
+                        &surfaces,
                     )
                 });
             ground.update(next_ground);
@@ -357,7 +348,7 @@ pub fn find_ground(
         */
 
         // If we hit something, just get back up instead of waiting.
-        if ctx.contacts_with(entity).next().is_some() {
+        if B::in_contact(&backend, entity) {
             caster.skip_ground_check_timer = 0.0;
         }
     }
@@ -434,88 +425,8 @@ impl CastResult {
     }
 }
 
-impl CastResult {
-    /// Use the first shape in the shape-cast as the cast result.
-    pub fn from_toi1(toi: Toi) -> Option<Self> {
-        toi.details.map(|details| Self {
-            toi: toi.toi,
-            normal: details.normal1,
-            point: details.witness1,
-        })
-    }
-
-    /// Use the second shape in the shape-cast as the cast result.
-    pub fn from_toi2(toi: Toi) -> Option<Self> {
-        toi.details.map(|details| Self {
-            toi: toi.toi,
-            normal: details.normal2,
-            point: details.witness2,
-        })
-    }
-}
-
-impl From<RayIntersection> for CastResult {
-    fn from(intersection: RayIntersection) -> Self {
-        Self {
-            toi: intersection.toi,
-            normal: intersection.normal,
-            point: intersection.point,
-        }
-    }
-}
-
-/// Get a list of contacts for a given shape.
-pub fn contact_manifolds(
-    ctx: &RapierContext,
-    position: Vec3,
-    rotation: Quat,
-    collider: &Collider,
-    filter: &QueryFilter,
-) -> Vec<(Entity, ContactManifold)> {
-    let physics_scale = ctx.physics_scale();
-
-    let shape = &collider.raw;
-    let shape_iso = Isometry3 {
-        translation: (position * physics_scale).into(),
-        rotation: rotation.into(),
-    };
-
-    let shape_aabb = shape.compute_aabb(&shape_iso).loosened(FUDGE);
-
-    let mut manifolds = Vec::new();
-    ctx.query_pipeline
-        .colliders_with_aabb_intersecting_aabb(&shape_aabb, |handle| {
-            if let Some(collider) = ctx.colliders.get(*handle) {
-                if RapierContext::with_query_filter(&ctx, *filter, |rapier_filter| {
-                    rapier_filter.test(&ctx.bodies, *handle, collider)
-                }) {
-                    let mut new_manifolds = Vec::new();
-                    let pos12 = shape_iso.inv_mul(collider.position());
-                    let _ = DefaultQueryDispatcher.contact_manifolds(
-                        &pos12,
-                        shape.as_ref(),
-                        collider.shape(),
-                        0.01,
-                        &mut new_manifolds,
-                        &mut None,
-                    );
-
-                    if let Some(entity) = ctx.collider_entity(*handle) {
-                        manifolds
-                            .extend(new_manifolds.into_iter().map(|manifold| (entity, manifold)));
-                    }
-                }
-            }
-
-            true
-        });
-
-    manifolds
-}
-
 /// Parameters to robust ground shape/raycasting.
-#[derive(Clone)]
-pub struct GroundCastParams<'c, 'f> {
+pub struct GroundCastParams<'c, 'f, B: WanderlustBackend> {
     /// Position of the shape in world-space.
     pub position: Vec3,
     /// Rotation of the shape.
@@ -523,28 +434,43 @@ pub struct GroundCastParams<'c, 'f> {
     /// Direction to cast, this should be normalized.
     pub direction: Vec3,
     /// Shape to use in the shapecast.
-    pub shape: &'c Collider,
+    pub shape: &'c B::Shape,
     /// Maximum distance we should cast.
     pub max_toi: f32,
     /// Filter collider types/entities from this ground cast.
-    pub filter: QueryFilter<'f>,
+    pub filter: CastFilter<'f>,
+    /// Physics backend to cast with.
+    pub backend: PhantomData<B>,
+}
+
+impl<'c, 'f, B: WanderlustBackend> Clone for GroundCastParams<'c, 'f, B> {
+    fn clone(&self) -> Self {
+        Self {
+            position: self.position,
+            rotation: self.rotation,
+            direction: self.direction,
+            shape: self.shape,
+            max_toi: self.max_toi,
+            filter: self.filter,
+            backend: PhantomData,
+        }
+    }
 }
 
 /// Arbitrary "slop"/"fudge" amount to adjust various things.
 pub const FUDGE: f32 = 0.05;
 
-impl<'c, 'f> GroundCastParams<'c, 'f> {
+impl<'c, 'f, B: WanderlustBackend> GroundCastParams<'c, 'f, B> {
     /// Ground cast
     pub fn cast_iters(
         &mut self,
-        ctx: &RapierContext,
-        globals: &Query<&GlobalTransform>,
+        ctx: &BackendQuery<B>,
         up_vector: Vec3,
         iterations: usize,
         gizmos: &mut Gizmos,
     ) -> Option<(Entity, CastResult)> {
         for _ in 0..iterations {
-            if let Some((entity, cast)) = self.cast(ctx, globals, up_vector, gizmos) {
+            if let Some((entity, cast)) = self.cast(ctx, up_vector, gizmos) {
                 return Some((entity, cast));
             }
         }
@@ -556,17 +482,14 @@ impl<'c, 'f> GroundCastParams<'c, 'f> {
     /// if the cast fails to find viable ground.
     pub fn viable_cast_iters(
         &mut self,
-        ctx: &RapierContext,
-        globals: &Query<&GlobalTransform>,
+        ctx: &BackendQuery<B>,
         max_angle: f32,
         up_vector: Vec3,
         iterations: usize,
         gizmos: &mut Gizmos,
     ) -> Option<(Entity, CastResult)> {
         for _ in 0..iterations {
-            if let Some((entity, cast)) =
-                self.viable_cast(ctx, globals, up_vector, max_angle, gizmos)
-            {
+            if let Some((entity, cast)) = self.viable_cast(ctx, up_vector, max_angle, gizmos) {
                 return Some((entity, cast));
             }
         }
@@ -577,12 +500,11 @@ impl<'c, 'f> GroundCastParams<'c, 'f> {
     /// Find the first ground we can cast to.
     pub fn cast(
         &mut self,
-        ctx: &RapierContext,
-        globals: &Query<&GlobalTransform>,
+        ctx: &BackendQuery<B>,
         up_vector: Vec3,
         gizmos: &mut Gizmos,
     ) -> Option<(Entity, CastResult)> {
-        self.correct_penetrations(ctx, globals);
+        self.correct_penetrations(ctx);
 
         let (entity, mut cast) = if let Some((entity, cast)) = self.cast_shape(ctx, gizmos) {
             (entity, cast)
@@ -609,13 +531,12 @@ impl<'c, 'f> GroundCastParams<'c, 'f> {
     /// Robust viable ground casting.
     pub fn viable_cast(
         &mut self,
-        ctx: &RapierContext,
-        globals: &Query<&GlobalTransform>,
+        ctx: &BackendQuery<B>,
         up_vector: Vec3,
         max_angle: f32,
         gizmos: &mut Gizmos,
     ) -> Option<(Entity, CastResult)> {
-        let Some((entity, cast)) = self.cast(ctx, globals, up_vector, gizmos) else {
+        let Some((entity, cast)) = self.cast(ctx, up_vector, gizmos) else {
             return None;
         };
 
@@ -628,17 +549,11 @@ impl<'c, 'f> GroundCastParams<'c, 'f> {
     }
 
     /// Push the ground cast parameteres out of any colliders it is penetrating.
-    pub fn correct_penetrations(&mut self, ctx: &RapierContext, globals: &Query<&GlobalTransform>) {
+    pub fn correct_penetrations(&mut self, ctx: &BackendQuery<B>) {
         let manifolds =
-            contact_manifolds(ctx, self.position, self.rotation, self.shape, &self.filter);
-
-        for (entity, manifold) in &manifolds {
-            let local_normal: Vec3 = manifold.local_n2.into();
+            B::contact_manifolds(ctx, self.shape, self.position, self.rotation, self.filter);
 
-            let Ok(contact_global) = globals.get(*entity) else {
-                continue;
-            };
-            let normal = contact_global.to_scale_rotation_translation().1 * local_normal;
+        for &(_, normal) in &manifolds {
             //for point in &manifold.points {
             let correction = normal * 0.05;
             self.position += correction;
@@ -649,30 +564,21 @@ impl<'c, 'f> GroundCastParams<'c, 'f> {
     /// Cast a shape downwards using the parameters.
     pub fn cast_shape(
         &self,
-        ctx: &RapierContext,
+        ctx: &BackendQuery<B>,
         gizmos: &mut Gizmos,
     ) -> Option<(Entity, CastResult)> {
-        let Some((entity, toi)) = ctx.cast_shape(
+        let Some((entity, cast)) = B::cast_shape(
+            ctx,
+            self.shape,
             self.position,
             self.rotation,
             self.direction,
-            self.shape,
             self.max_toi,
-            true,
             self.filter,
         ) else {
             return None;
         };
 
-        if toi.toi <= std::f32::EPSILON {
-            return None;
-        }
-
-        let (entity, cast) = (entity, CastResult::from_toi1(toi));
-        let Some(cast) = cast else {
-            return None;
-        };
-
         gizmos.ray(self.position, self.direction * cast.toi, Color::BLUE);
         gizmos.sphere(
             self.position + self.direction * cast.toi,
@@ -687,19 +593,15 @@ impl<'c, 'f> GroundCastParams<'c, 'f> {
     /// A fallback to a simple raycasting downwards.
     ///
     /// Used in the case that we are unable to correct penetration.
-    pub fn cast_ray(&self, ctx: &RapierContext) -> Option<(Entity, CastResult)> {
+    pub fn cast_ray(&self, ctx: &BackendQuery<B>) -> Option<(Entity, CastResult)> {
         // This should only occur if the controller fails to correct penetration
         // of colliders.
 
         // local shape offset from origin to bottom of shape
-        let offset = self
-            .shape
-            .cast_local_ray(Vec3::ZERO, self.direction, 10.0, false)
-            .unwrap_or(0.);
+        let offset = B::shape_extent(self.shape, self.direction);
         let ray_pos = self.position + self.direction * offset;
 
-        ctx.cast_ray_and_get_normal(ray_pos, self.direction, self.max_toi, true, self.filter)
-            .map(|(entity, inter)| (entity, inter.into()))
+        B::cast_ray(ctx, ray_pos, self.direction, self.max_toi, self.filter)
     }
 
     /// Adjust to cast down the slope of the currently found ground.
@@ -727,7 +629,7 @@ impl<'c, 'f> GroundCastParams<'c, 'f> {
     /// on just the shapecast (which tends to interpolate normals while on edges).
     pub fn sample_normals(
         &self,
-        ctx: &RapierContext,
+        ctx: &BackendQuery<B>,
         cast: CastResult,
         up_vector: Vec3,
         gizmos: &mut Gizmos,
@@ -746,11 +648,11 @@ impl<'c, 'f> GroundCastParams<'c, 'f> {
         let valid_radius = FUDGE * 2.0;
         gizmos.sphere(cast.point, Quat::IDENTITY, valid_radius, Color::RED); // Bounding sphere of valid ray normals
         for sample in samples {
-            let Some((_, inter)) = ctx.cast_ray_and_get_normal(
+            let Some((_, inter)) = B::cast_ray(
+                ctx,
                 ray_origin - sample * FUDGE,
                 ray_dir,
                 self.max_toi,
-                true,
                 self.filter,
             ) else {
                 continue;
@@ -13,4 +13,6 @@ pub struct ControllerInput {
     pub movement: Vec3,
     /// This field represents if the jump control is currently pressed.
     pub jumping: bool,
+    /// This field represents if the crouch control is currently pressed. See [`Crouch`](crate::Crouch).
+    pub crouching: bool,
 }
@@ -0,0 +1,390 @@
+use crate::controller::*;
+use crate::SpringStrength;
+use std::marker::PhantomData;
+
+/// Lets the controller grab ledges while airborne, hang from them, shimmy along them,
+/// drop off or climb up onto them.
+///
+/// A ledge is a wall found by the [`WallCaster`] with a walkable top surface within
+/// reach above the controller. While hanging or climbing, the [`Float`], [`Gravity`],
+/// [`Movement`], [`Jump`] and [`StepUp`] forces are suspended and a spring holds the
+/// controller in the hang pose instead. Pressing jump climbs up, steering away from the
+/// wall drops off and steering along the wall shimmies. Shimmying past the end of a
+/// ledge drops off.
+///
+/// This is opt-in, add it next to the [`Controller`] bundle to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct LedgeGrab {
+    /// Lowest a ledge can be, above the controller's origin, to be grabbed.
+    pub min_height: f32,
+    /// Highest a ledge can be, above the controller's origin, to be grabbed.
+    pub max_height: f32,
+    /// How much of the top of the ledge needs to be free for it to be grabbed.
+    pub min_depth: f32,
+    /// Fastest the controller can be moving upwards, relative to the wall, and still grab a ledge.
+    pub max_rise_speed: f32,
+    /// How far below the ledge the controller's origin hangs.
+    pub hang_height: f32,
+    /// How far out from the wall the controller's origin hangs.
+    pub hang_distance: f32,
+    /// How strongly to pull the controller into the hanging and climbing poses.
+    pub spring: Spring,
+    /// Speed to shimmy along the ledge at.
+    pub shimmy_speed: f32,
+    /// How far past the edge, onto the top of the ledge, to climb.
+    pub climb_distance: f32,
+    /// Longest a climb can take before letting go of the ledge.
+    pub max_climb_duration: f32,
+    /// Time left for the current climb.
+    pub climb_timer: f32,
+    /// How long after letting go of a ledge before another one can be grabbed.
+    pub cooldown_duration: f32,
+    /// Timer for tracking the cooldown.
+    pub cooldown_timer: f32,
+    /// What the controller is currently doing with a ledge.
+    pub state: LedgeState,
+    /// Was jump pressed last frame.
+    pub pressed_last_frame: bool,
+}
+
+impl Default for LedgeGrab {
+    fn default() -> Self {
+        Self {
+            min_height: 0.4,
+            max_height: 1.1,
+            min_depth: 0.1,
+            max_rise_speed: 2.0,
+            hang_height: 0.8,
+            hang_distance: 0.35,
+            spring: Spring {
+                strength: SpringStrength::AngularFrequency(15.0),
+                damping: 1.0,
+            },
+            shimmy_speed: 1.5,
+            climb_distance: 0.5,
+            max_climb_duration: 1.0,
+            climb_timer: 0.0,
+            cooldown_duration: 0.3,
+            cooldown_timer: 0.0,
+            state: LedgeState::None,
+            pressed_last_frame: false,
+        }
+    }
+}
+
+impl LedgeGrab {
+    /// Is the controller currently hanging from a ledge.
+    pub fn hanging(&self) -> bool {
+        matches!(self.state, LedgeState::Hanging(_))
+    }
+
+    /// Is the controller currently climbing up a ledge.
+    pub fn climbing(&self) -> bool {
+        matches!(self.state, LedgeState::Climbing(_))
+    }
+
+    /// Let go of the current ledge, if any.
+    pub fn release(&mut self) {
+        if self.state != LedgeState::None {
+            self.state = LedgeState::None;
+            self.cooldown_timer = self.cooldown_duration;
+        }
+    }
+}
+
+/// What a controller is doing with a ledge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+pub enum LedgeState {
+    /// Not holding onto a ledge.
+    #[default]
+    None,
+    /// Hanging from a ledge.
+    Hanging(Ledge),
+    /// Climbing up onto a ledge.
+    Climbing(Ledge),
+}
+
+/// A grabbable ledge.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct Ledge {
+    /// Entity the ledge belongs to.
+    pub entity: Entity,
+    /// Point on the edge, level with the top surface and the wall face.
+    pub point: Vec3,
+    /// Normal of the wall below the ledge, perpendicular to the up vector.
+    pub normal: Vec3,
+    /// Linear velocity of the ledge at the wall contact.
+    pub point_velocity: Vec3,
+}
+
+impl<'c, 'f, B: WanderlustBackend> GroundCastParams<'c, 'f, B> {
+    /// Probe for a grabbable ledge at the top of `wall`.
+    ///
+    /// `position` should be the controller's origin, the shape, rotation and filter are
+    /// used to make sure the controller can stand on top of the ledge at `stand_height`.
+    pub fn probe_ledge(
+        &self,
+        ctx: &BackendQuery<B>,
+        ledge_grab: &LedgeGrab,
+        wall: &Wall,
+        stand_height: f32,
+        max_ground_angle: f32,
+        up_vector: Vec3,
+    ) -> Option<Ledge> {
+        let normal = wall.cast.normal.reject_from(up_vector).normalize_or_zero();
+        if normal == Vec3::ZERO {
+            return None;
+        }
+
+        // Look down onto the top of the wall from just above the highest ledge we can reach.
+        let base = self.position.dot(up_vector);
+        let wall_height = wall.cast.point.dot(up_vector);
+        let over = wall.cast.point - normal * ledge_grab.min_depth
+            + up_vector * (base + ledge_grab.max_height + FUDGE - wall_height);
+        let (_, top) = B::cast_ray(
+            ctx,
+            over,
+            -up_vector,
+            ledge_grab.max_height - ledge_grab.min_height + FUDGE,
+            self.filter,
+        )?;
+
+        // Starting inside the wall means it is taller than we can reach.
+        if top.toi <= 0.0 || !top.viable(up_vector, max_ground_angle) {
+            return None;
+        }
+
+        let height = top.point.dot(up_vector) - base;
+        if height < ledge_grab.min_height || height > ledge_grab.max_height {
+            return None;
+        }
+
+        // Make sure the collider fits on top by dropping it from the standing pose.
+        let point = top.point + normal * (wall.cast.point - top.point).dot(normal);
+        let stand = point - normal * ledge_grab.climb_distance + up_vector * stand_height;
+        let (_, landing) = B::cast_shape(
+            ctx,
+            self.shape,
+            stand,
+            self.rotation,
+            -up_vector,
+            stand_height,
+            self.filter,
+        )?;
+        let clearance = stand_height - B::shape_extent(self.shape, -up_vector);
+        if landing.toi < clearance - FUDGE {
+            return None;
+        }
+
+        Some(Ledge {
+            entity: wall.entity,
+            point,
+            normal,
+            point_velocity: wall.point_velocity,
+        })
+    }
+}
+
+/// Grab, hang from, shimmy along and climb up ledges.
+///
+/// This replaces the other controller forces while holding onto a ledge, so it runs
+/// after them.
+#[allow(clippy::type_complexity)]
+pub fn ledge_grab<B: WanderlustBackend>(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut LedgeGrab,
+        (
+            &mut MovementForce,
+            &mut JumpForce,
+            &mut FloatForce,
+            &mut GravityForce,
+            &mut StepUpForce,
+        ),
+        &mut Jump,
+        &ControllerInput,
+        &WallCaster,
+        &WallCast,
+        &GroundCaster,
+        &Grounded,
+        &Float,
+        &Gravity,
+        &ControllerVelocity,
+        &ControllerMass,
+        Option<&Mantle>,
+    )>,
+    globals: Query<&GlobalTransform>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+) {
+    let dt = B::dt(&backend);
+    if dt == 0.0 {
+        return;
+    }
+
+    for (
+        entity,
+        global,
+        mut ledge_grab,
+        (mut movement_force, mut jump_force, mut float_force, mut gravity_force, mut step_up_force),
+        mut jump,
+        input,
+        wall_caster,
+        wall_cast,
+        ground_caster,
+        grounded,
+        float,
+        gravity,
+        velocity,
+        mass,
+        mantle,
+    ) in &mut query
+    {
+        if ledge_grab.cooldown_timer > 0.0 {
+            ledge_grab.cooldown_timer = (ledge_grab.cooldown_timer - dt).max(0.0);
+        }
+
+        let jump_inputted = input.jumping && !ledge_grab.pressed_last_frame;
+        ledge_grab.pressed_last_frame = input.jumping;
+
+        let Some(shape) = B::collider_shape(&backend, entity) else {
+            continue;
+        };
+
+        let up_vector = gravity.up_vector;
+        let input_dir = input.movement.reject_from(up_vector).normalize_or_zero();
+        let (_, rotation, position) = global.to_scale_rotation_translation();
+
+        let predicate = |collider| {
+            collider != entity
+                && !wall_caster.exclude_from_wall.contains(&collider)
+                && !ground_caster.exclude_from_ground.contains(&collider)
+        };
+        let filter = CastFilter::new().exclude_sensors().predicate(&predicate);
+        let params = GroundCastParams::<B> {
+            position,
+            rotation,
+            direction: -up_vector,
+            shape: &shape,
+            max_toi: ledge_grab.max_height,
+            filter,
+            backend: PhantomData,
+        };
+        let probe = |ledge_grab: &LedgeGrab, wall: &Wall| {
+            params.probe_ledge(
+                &backend,
+                ledge_grab,
+                wall,
+                float.distance,
+                ground_caster.max_ground_angle,
+                up_vector,
+            )
+        };
+
+        let next_state = match ledge_grab.state {
+            LedgeState::None => {
+                let grab = wall_cast.current().filter(|wall| {
+                    let normal = wall.cast.normal.reject_from(up_vector).normalize_or_zero();
+                    let rise = (velocity.linear - wall.point_velocity).dot(up_vector);
+                    !**grounded
+                        && !mantle.is_some_and(|mantle| mantle.mantling())
+                        && ledge_grab.cooldown_timer <= 0.0
+                        && rise <= ledge_grab.max_rise_speed
+                        && input_dir.dot(normal) <= FUDGE
+                });
+                match grab.and_then(|wall| probe(&ledge_grab, wall)) {
+                    Some(ledge) => {
+                        jump.jump_timer = 0.0;
+                        LedgeState::Hanging(ledge)
+                    }
+                    None => LedgeState::None,
+                }
+            }
+            LedgeState::Hanging(ledge) => {
+                if jump_inputted {
+                    jump.jump_timer = 0.0;
+                    jump.buffer_timer = 0.0;
+                    ledge_grab.climb_timer = ledge_grab.max_climb_duration;
+                    LedgeState::Climbing(ledge)
+                } else if input_dir.dot(ledge.normal) > 0.5 {
+                    LedgeState::None
+                } else {
+                    // Follow the wall we are holding onto rather than the wall cast, which
+                    // looks wherever the controller is heading.
+                    let wall = B::cast_shape(
+                        &backend,
+                        &shape,
+                        position + ledge.normal * FUDGE,
+                        rotation,
+                        -ledge.normal,
+                        wall_caster.cast_length + FUDGE,
+                        filter,
+                    )
+                    .map(|(hit, cast)| {
+                        Wall::from_cast::<B>(hit, cast, -ledge.normal, &backend, &globals)
+                    });
+                    match wall.and_then(|wall| probe(&ledge_grab, &wall)) {
+                        Some(ledge) => LedgeState::Hanging(ledge),
+                        None => LedgeState::None,
+                    }
+                }
+            }
+            LedgeState::Climbing(ledge) => {
+                ledge_grab.climb_timer = (ledge_grab.climb_timer - dt).max(0.0);
+                if ledge_grab.climb_timer > 0.0 {
+                    LedgeState::Climbing(ledge)
+                } else {
+                    LedgeState::None
+                }
+            }
+        };
+
+        let (ledge, climbing) = match next_state {
+            LedgeState::None => {
+                ledge_grab.release();
+                continue;
+            }
+            LedgeState::Hanging(ledge) => (ledge, false),
+            LedgeState::Climbing(ledge) => (ledge, true),
+        };
+
+        let tangent = ledge.normal.cross(up_vector).normalize_or_zero();
+        let relative_velocity = velocity.linear - ledge.point_velocity;
+        let along = relative_velocity.dot(tangent);
+
+        let (target, goal_along) = if climbing {
+            // Rise in front of the wall until clear of the edge, then move onto the top.
+            let bottom = position.dot(up_vector) - B::shape_extent(&shape, -up_vector);
+            let offset = if bottom < ledge.point.dot(up_vector) + FUDGE {
+                ledge.normal * ledge_grab.hang_distance
+            } else {
+                -ledge.normal * ledge_grab.climb_distance
+            };
+            (ledge.point + offset + up_vector * float.distance, 0.0)
+        } else {
+            let hang = ledge.point - up_vector * ledge_grab.hang_height
+                + ledge.normal * ledge_grab.hang_distance;
+            (hang, input_dir.dot(tangent) * ledge_grab.shimmy_speed)
+        };
+
+        let displacement = (target - position).reject_from(tangent);
+        let mass_vec = Vec3::splat(mass.mass);
+        let spring = displacement * ledge_grab.spring.strength.get(mass_vec)
+            - relative_velocity.reject_from(tangent) * ledge_grab.spring.damp_coefficient(mass_vec);
+        let shimmy = tangent * (goal_along - along) * mass.mass / dt;
+
+        movement_force.linear = spring + shimmy;
+        jump_force.linear = Vec3::ZERO;
+        float_force.linear = Vec3::ZERO;
+        gravity_force.linear = Vec3::ZERO;
+        step_up_force.linear = Vec3::ZERO;
+
+        // Finished climbing once standing on top of the ledge.
+        if climbing && displacement.length() < ledge_grab.hang_distance * 0.5 {
+            ledge_grab.release();
+        } else {
+            ledge_grab.state = next_state;
+        }
+    }
+}
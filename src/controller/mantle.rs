@@ -0,0 +1,290 @@
+use crate::controller::*;
+use std::marker::PhantomData;
+
+/// Lets the controller vault over or mantle onto obstacles in front of it.
+///
+/// Obstacles between [`Mantle::min_height`] and [`Mantle::max_height`] above the ground
+/// are climbed along a short scripted arc: the controller rises until clear of the
+/// obstacle, then moves over onto its top.
+/// Ground checks are skipped and the other controller forces are suspended for the
+/// duration of the arc. [`MantleStarted`] and [`MantleEnded`] are sent so animations
+/// can follow along.
+///
+/// This is opt-in, add it next to the [`Controller`] bundle to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct Mantle {
+    /// Lowest obstacle, measured from the current ground, to mantle.
+    pub min_height: f32,
+    /// Tallest obstacle, measured from the current ground, to mantle.
+    pub max_height: f32,
+    /// How much of the top of the obstacle needs to be free to stand on.
+    pub min_depth: f32,
+    /// How far ahead of the collider to look for obstacles, in addition to the distance
+    /// travelled this frame.
+    pub probe_distance: f32,
+    /// How far past the edge, onto the top of the obstacle, the arc ends.
+    pub landing_distance: f32,
+    /// How far the bottom of the collider clears the top of the obstacle at the peak of the arc.
+    pub clearance: f32,
+    /// How long the arc takes.
+    pub duration: f32,
+    /// Only mantle when jump is pressed, otherwise moving into an obstacle is enough.
+    pub require_jump: bool,
+    /// The arc currently being followed.
+    pub arc: Option<MantleArc>,
+    /// Time spent following the current arc.
+    pub elapsed: f32,
+    /// Was jump pressed last frame.
+    pub pressed_last_frame: bool,
+}
+
+impl Default for Mantle {
+    fn default() -> Self {
+        Self {
+            min_height: 0.4,
+            max_height: 1.2,
+            min_depth: 0.2,
+            probe_distance: 0.3,
+            landing_distance: 0.4,
+            clearance: 0.1,
+            duration: 0.4,
+            require_jump: true,
+            arc: None,
+            elapsed: 0.0,
+            pressed_last_frame: false,
+        }
+    }
+}
+
+impl Mantle {
+    /// Is the controller currently mantling.
+    pub fn mantling(&self) -> bool {
+        self.arc.is_some()
+    }
+
+    /// 0..1 progress along the current arc.
+    pub fn progress(&self) -> f32 {
+        (self.elapsed / self.duration).clamp(0.0, 1.0)
+    }
+}
+
+/// The path the controller follows when mantling.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct MantleArc {
+    /// Obstacle being mantled.
+    pub obstacle: Entity,
+    /// Where the controller started mantling from.
+    pub start: Vec3,
+    /// Where the controller will end up, floating on top of the obstacle.
+    pub end: Vec3,
+    /// Height, along the up vector, of the controller's origin at the top of the arc.
+    pub peak: f32,
+}
+
+impl MantleArc {
+    /// Position along the arc at 0..1 `progress`.
+    ///
+    /// The first half rises to the peak, the second half moves over the obstacle while
+    /// settling down onto it.
+    pub fn position(&self, progress: f32, up_vector: Vec3) -> Vec3 {
+        let smoothstep = |t: f32| {
+            let t = t.clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        };
+        let rise = smoothstep(progress * 2.0);
+        let over = smoothstep(progress * 2.0 - 1.0);
+
+        let start_height = self.start.dot(up_vector);
+        let end_height = self.end.dot(up_vector);
+        let height =
+            start_height + (self.peak - start_height) * rise + (end_height - self.peak) * over;
+
+        let start = self.start.reject_from(up_vector);
+        let end = self.end.reject_from(up_vector);
+        start.lerp(end, over) + up_vector * height
+    }
+}
+
+/// Sent when a controller starts mantling an obstacle.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct MantleStarted {
+    /// Controller mantling.
+    pub entity: Entity,
+    /// Entity being mantled.
+    pub obstacle: Entity,
+    /// Height of the obstacle above the ground the controller was standing on.
+    pub height: f32,
+    /// How long the mantle will take.
+    pub duration: f32,
+}
+
+/// Sent when a controller finishes mantling an obstacle.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct MantleEnded {
+    /// Controller that was mantling.
+    pub entity: Entity,
+    /// Entity that was mantled.
+    pub obstacle: Entity,
+}
+
+/// Start mantles and move the controller along the mantle arc.
+///
+/// This replaces the other controller forces while mantling, so it runs after them,
+/// except for [`jump_force`] which runs afterwards and skips jumping while mantling.
+#[allow(clippy::type_complexity)]
+pub fn mantle<B: WanderlustBackend>(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut Mantle,
+        (
+            &mut MovementForce,
+            &mut JumpForce,
+            &mut FloatForce,
+            &mut GravityForce,
+            &mut StepUpForce,
+        ),
+        &mut Jump,
+        &mut GroundCaster,
+        &ViableGroundCast,
+        &Grounded,
+        &ControllerInput,
+        &Float,
+        &Gravity,
+        &ControllerVelocity,
+        &ControllerMass,
+        Option<&LedgeGrab>,
+    )>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+    mut started: EventWriter<MantleStarted>,
+    mut ended: EventWriter<MantleEnded>,
+) {
+    let dt = B::dt(&backend);
+    if dt == 0.0 {
+        return;
+    }
+
+    for (
+        entity,
+        global,
+        mut mantle,
+        (mut movement_force, mut jump_force, mut float_force, mut gravity_force, mut step_up_force),
+        mut jump,
+        mut ground_caster,
+        viable_ground,
+        grounded,
+        input,
+        float,
+        gravity,
+        velocity,
+        mass,
+        ledge_grab,
+    ) in &mut query
+    {
+        let jump_inputted = input.jumping && !mantle.pressed_last_frame;
+        mantle.pressed_last_frame = input.jumping;
+
+        let up_vector = gravity.up_vector;
+        let position = global.translation();
+
+        if mantle.arc.is_none() {
+            let holding_ledge =
+                ledge_grab.is_some_and(|ledge_grab| ledge_grab.state != LedgeState::None);
+            let ground = match viable_ground.current() {
+                Some(ground) if **grounded && !holding_ledge => ground,
+                _ => continue,
+            };
+            if mantle.require_jump && !jump_inputted {
+                continue;
+            }
+
+            let direction = input.movement.reject_from(up_vector).normalize_or_zero();
+            if direction == Vec3::ZERO {
+                continue;
+            }
+            let Some(shape) = B::collider_shape(&backend, entity) else {
+                continue;
+            };
+
+            let relative_velocity = velocity.linear - ground.point_velocity;
+            let distance = mantle.probe_distance + relative_velocity.dot(direction).max(0.0) * dt;
+
+            let predicate = |collider| {
+                collider != entity && !ground_caster.exclude_from_ground.contains(&collider)
+            };
+            let rotation = global.to_scale_rotation_translation().1;
+            let params = GroundCastParams::<B> {
+                position,
+                rotation,
+                direction,
+                shape: &shape,
+                max_toi: distance,
+                filter: CastFilter::new().exclude_sensors().predicate(&predicate),
+                backend: PhantomData,
+            };
+
+            let Some(obstacle) = params.probe_step(
+                &backend,
+                mantle.max_height,
+                mantle.min_depth,
+                ground,
+                ground_caster.max_ground_angle,
+                up_vector,
+            ) else {
+                continue;
+            };
+            if obstacle.height < mantle.min_height {
+                continue;
+            }
+
+            let top = obstacle.cast.point.dot(up_vector);
+            let end = (obstacle.cast.point + direction * mantle.landing_distance)
+                .reject_from(up_vector)
+                + up_vector * (top + float.distance);
+            let peak = top + B::shape_extent(&shape, -up_vector) + mantle.clearance;
+            let obstacle_entity = B::collider_body(&backend, obstacle.entity);
+            mantle.arc = Some(MantleArc {
+                obstacle: obstacle_entity,
+                start: position,
+                end,
+                peak: peak.max(end.dot(up_vector)),
+            });
+            mantle.elapsed = 0.0;
+
+            ground_caster.skip_ground_check_timer = mantle.duration;
+            jump.reset_jump();
+            jump.buffer_timer = 0.0;
+
+            started.send(MantleStarted {
+                entity,
+                obstacle: obstacle_entity,
+                height: obstacle.height,
+                duration: mantle.duration,
+            });
+        }
+
+        let Some(arc) = mantle.arc else {
+            continue;
+        };
+
+        mantle.elapsed += dt;
+        let target = arc.position(mantle.progress(), up_vector);
+        let goal_velocity = (target - position) / dt;
+
+        movement_force.linear = (goal_velocity - velocity.linear) * mass.mass / dt;
+        jump_force.linear = Vec3::ZERO;
+        float_force.linear = Vec3::ZERO;
+        gravity_force.linear = Vec3::ZERO;
+        step_up_force.linear = Vec3::ZERO;
+
+        if mantle.progress() >= 1.0 {
+            mantle.arc = None;
+            mantle.elapsed = 0.0;
+            ended.send(MantleEnded {
+                entity,
+                obstacle: arc.obstacle,
+            });
+        }
+    }
+}
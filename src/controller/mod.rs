@@ -1,16 +1,29 @@
-use bevy::prelude::*;
-use bevy_rapier3d::prelude::*;
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
 
+mod crouch;
+mod events;
+mod fall_damage;
 mod gravity;
+mod gravity_field;
 mod ground;
 mod input;
+mod ledge;
+mod mantle;
 mod movement;
 mod orientation;
+mod step;
+mod surface;
+mod wall;
+mod wall_run;
 
+use crate::backend::*;
 use crate::physics::*;
 use crate::Spring;
 
-pub use {gravity::*, ground::*, input::*, movement::*, orientation::*};
+pub use {
+    crouch::*, events::*, fall_damage::*, gravity::*, gravity_field::*, ground::*, input::*,
+    ledge::*, mantle::*, movement::*, orientation::*, step::*, surface::*, wall::*, wall_run::*,
+};
 
 /// Components required for calculating controller forces.
 #[derive(Bundle)]
@@ -32,11 +45,15 @@ pub struct Controller {
     pub grounded: Grounded,
     /// Force applied to the ground the controller is on.
     pub ground_force: GroundForce,
+    /// State used to send events such as [`Landed`] and [`LeftGround`].
+    pub event_state: ControllerEventState,
 
     /// Adjusting speed of the controller.
     pub movement: Movement,
     /// Calculated force for moving the controller.
     pub movement_force: MovementForce,
+    /// How the controller brakes.
+    pub friction: FrictionSettings,
 
     /// How the controller's jumping should behave.
     pub jump: Jump,
@@ -48,6 +65,9 @@ pub struct Controller {
     /// Calculated force for keeping the controller floating.
     pub float_force: FloatForce,
 
+    /// Calculated force for climbing steps, see [`StepUp`].
+    pub step_up_force: StepUpForce,
+
     /// How to keep the controller upright, as well as
     /// facing a specific direction.
     pub upright: Upright,
@@ -69,14 +89,17 @@ impl Default for Controller {
             viable_ground_cast: default(),
             grounded: default(),
             ground_force: default(),
+            event_state: default(),
 
             movement: default(),
             movement_force: default(),
+            friction: default(),
             jump: default(),
             jump_force: default(),
 
             float: default(),
             float_force: default(),
+            step_up_force: default(),
             upright: default(),
             upright_force: default(),
 
@@ -106,9 +129,10 @@ impl Default for ForceSettings {
 }
 
 /// Add all forces together into a single force to be applied to the physics engine.
-pub fn accumulate_forces(
+#[allow(clippy::type_complexity)]
+pub fn accumulate_forces<B: WanderlustBackend>(
     globals: Query<&GlobalTransform>,
-    masses: Query<&ReadMassProperties>,
+    backend: StaticSystemParam<B::SpatialQuery>,
     mut forces: Query<(
         &ForceSettings,
         &mut ControllerForce,
@@ -117,10 +141,19 @@ pub fn accumulate_forces(
         &UprightForce,
         &MovementForce,
         &JumpForce,
+        &StepUpForce,
         &GravityForce,
         &ViableGroundCast,
+        Option<(
+            &AirDrag,
+            &ControllerVelocity,
+            &ControllerMass,
+            &Gravity,
+            &Grounded,
+        )>,
     )>,
 ) {
+    let dt = B::dt(&backend);
     for (
         settings,
         mut force,
@@ -129,8 +162,10 @@ pub fn accumulate_forces(
         upright,
         movement,
         jump,
+        step_up,
         gravity,
         viable_ground,
+        air_drag,
     ) in &mut forces
     {
         /*
@@ -139,12 +174,35 @@ pub fn accumulate_forces(
             movement.linear, jump.linear, float.linear, gravity.linear
         );
         */
-        force.linear = movement.linear + jump.linear + float.linear + gravity.linear;
+        force.linear =
+            movement.linear + jump.linear + step_up.linear + float.linear + gravity.linear;
         force.angular = movement.angular + upright.angular;
         //force.angular = movement.angular;
 
+        // Both the drag clamp and the terminal velocity correction divide by these.
+        if let Some((air_drag, velocity, mass, gravity_settings, grounded)) =
+            air_drag.filter(|(_, _, mass, _, _)| dt > 0.0 && mass.mass > 0.0)
+        {
+            if !**grounded {
+                let speed = velocity.linear.length();
+                let drag = velocity.linear * speed * air_drag.quadratic_drag * mass.mass;
+                force.linear -= drag.clamp_length_max(speed * mass.mass / dt);
+            }
+
+            // Only take away whatever would push the fall speed past the limit.
+            if let Some(terminal_velocity) = air_drag.terminal_velocity {
+                let next_velocity = velocity.linear + force.linear * dt / mass.mass;
+                let fall_speed = -next_velocity.dot(gravity_settings.up_vector);
+                if fall_speed > terminal_velocity {
+                    force.linear +=
+                        gravity_settings.up_vector * (fall_speed - terminal_velocity) * mass.mass
+                            / dt;
+                }
+            }
+        }
+
         let opposing_force = -(movement.linear * settings.opposing_movement_force_scale
-            + (jump.linear + float.linear) * settings.opposing_force_scale);
+            + (jump.linear + step_up.linear + float.linear) * settings.opposing_force_scale);
 
         if let Some(ground) = viable_ground.current() {
             let ground_global = match globals.get(ground.entity) {
@@ -152,13 +210,9 @@ pub fn accumulate_forces(
                 _ => &GlobalTransform::IDENTITY,
             };
 
-            let ground_mass = if let Ok(mass) = masses.get(ground.entity) {
-                (**mass).clone()
-            } else {
-                MassProperties::default()
-            };
+            let ground_mass = B::mass(&backend, ground.entity);
 
-            let com = ground_global.transform_point(ground_mass.local_center_of_mass);
+            let com = ground_global.transform_point(ground_mass.com);
             ground_force.linear = opposing_force;
             ground_force.angular = (ground.cast.point - com).cross(opposing_force);
 
@@ -6,8 +6,10 @@ use crate::{controller::*, spring::Strength};
 #[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component, Default)]
 pub struct Movement {
-    /// How fast the controller will get to the `max_speed`.
-    pub acceleration: Strength,
+    /// How the controller speeds up, slows down and turns around while grounded.
+    pub ground: MovementProfile,
+    /// How the controller speeds up, slows down and turns around while airborne.
+    pub air: MovementProfile,
     /// How fast our controller will move.
     pub max_speed: f32,
     /// Scales movement force. This is useful to ensure movement does not
@@ -34,7 +36,8 @@ pub enum ForceScale {
 impl Default for Movement {
     fn default() -> Self {
         Self {
-            acceleration: Strength::Scaled(10.0),
+            ground: default(),
+            air: default(),
             max_speed: 5.0,
             force_scale: default(),
             slip_force_scale: Vec3::splat(1.0),
@@ -42,7 +45,76 @@ impl Default for Movement {
     }
 }
 
+/// Strengths used for each kind of movement, see [`Movement::ground`] and
+/// [`Movement::air`].
+#[derive(Debug, Clone, Reflect)]
+pub struct MovementProfile {
+    /// Used when speeding up towards the input direction.
+    pub acceleration: SpeedCurve,
+    /// Used to slow down when there is no input, on top of friction.
+    pub deceleration: SpeedCurve,
+    /// Used when the input opposes the current velocity.
+    pub turn_around: SpeedCurve,
+}
+
+impl Default for MovementProfile {
+    fn default() -> Self {
+        Self::from_acceleration(Strength::Scaled(10.0))
+    }
+}
+
+impl MovementProfile {
+    /// Profile that turns around as fast as it accelerates, and leaves slowing down
+    /// to friction.
+    pub fn from_acceleration(acceleration: Strength) -> Self {
+        Self {
+            acceleration: SpeedCurve::new(acceleration.clone()),
+            deceleration: SpeedCurve::new(Strength::Scaled(0.0)),
+            turn_around: SpeedCurve::new(acceleration),
+        }
+    }
+}
+
+/// A [`Strength`] that can vary with the controller's current speed.
+#[derive(Debug, Clone, Reflect)]
+pub struct SpeedCurve {
+    /// Strength at every speed, before the curve is applied.
+    pub strength: Strength,
+    /// A function taking the current speed as a fraction of [`Movement::max_speed`],
+    /// which returns a modifier to multiply `strength` by.
+    ///
+    /// For example `|speed| 1.0 - speed * 0.5` accelerates quickly from standing and
+    /// eases into the top speed.
+    #[reflect(ignore)]
+    pub curve: Option<fn(f32) -> f32>,
+}
+
+impl SpeedCurve {
+    /// Constant strength, regardless of speed.
+    pub fn new(strength: Strength) -> Self {
+        Self {
+            strength,
+            curve: None,
+        }
+    }
+
+    /// Calculate the strength at `speed_fraction` of the max speed.
+    pub fn get(&self, speed_fraction: f32, mass: f32, dt: f32) -> f32 {
+        let modifier = self.curve.map_or(1.0, |curve| curve(speed_fraction));
+        self.strength.get(mass, dt) * modifier.max(0.0)
+    }
+}
+
 impl Movement {
+    /// Profile to use depending on whether the controller is grounded.
+    pub fn profile(&self, grounded: bool) -> &MovementProfile {
+        if grounded {
+            &self.ground
+        } else {
+            &self.air
+        }
+    }
+
     /// Calculate force scale.
     pub fn force_scale(&self, gravity: &Gravity) -> Vec3 {
         match self.force_scale {
@@ -61,6 +133,92 @@ impl Movement {
     }
 }
 
+/// Changes how much control the controller has while airborne.
+///
+/// This is opt-in, add it next to the [`Controller`] bundle to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct AirControl {
+    /// Multiplier for the [`Movement::air`] acceleration and turn around strengths.
+    pub acceleration_multiplier: f32,
+    /// How fast the controller can move itself while airborne, [`Movement::max_speed`]
+    /// if `None`.
+    pub max_speed: Option<f32>,
+    /// Don't brake while airborne, so momentum above the max speed is kept.
+    ///
+    /// Input can still steer the controller, but letting go of it won't slow it down.
+    pub preserve_momentum: bool,
+}
+
+impl Default for AirControl {
+    fn default() -> Self {
+        Self {
+            acceleration_multiplier: 1.0,
+            max_speed: None,
+            preserve_momentum: false,
+        }
+    }
+}
+
+/// How the controller brakes, separately from how it accelerates.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct FrictionSettings {
+    /// How strongly friction brakes the controller while grounded, scaled by the
+    /// combined friction coefficient of the controller and the ground.
+    pub ground_strength: f32,
+    /// Friction coefficient used while airborne.
+    pub air_coefficient: f32,
+    /// How strongly friction brakes the controller while airborne, scaled by
+    /// `air_coefficient`.
+    pub air_strength: f32,
+    /// How to combine the friction coefficients of the controller and the ground.
+    pub combine_rule: FrictionCombineRule,
+    /// Scales the friction force per axis, on top of [`Movement::force_scale`].
+    pub axis_scale: Vec3,
+}
+
+impl Default for FrictionSettings {
+    fn default() -> Self {
+        Self {
+            ground_strength: 45.0,
+            air_coefficient: 0.25,
+            air_strength: 45.0,
+            combine_rule: FrictionCombineRule::Max,
+            axis_scale: Vec3::ONE,
+        }
+    }
+}
+
+/// How to combine the friction coefficients of the controller and the ground.
+///
+/// This mirrors the physics engines' combine rules, but is only used for the
+/// controller's own friction.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum FrictionCombineRule {
+    /// Average of the two coefficients.
+    Average,
+    /// Smallest of the two coefficients.
+    Min,
+    /// Product of the two coefficients.
+    Multiply,
+    /// Largest of the two coefficients.
+    #[default]
+    Max,
+}
+
+impl FrictionCombineRule {
+    /// Combine the `controller` and `ground` friction coefficients.
+    pub fn combine(&self, controller: f32, ground: f32) -> f32 {
+        match self {
+            Self::Average => (controller + ground) / 2.0,
+            Self::Min => controller.min(ground),
+            Self::Multiply => controller * ground,
+            Self::Max => controller.max(ground),
+        }
+    }
+}
+
 /// Calculated impulse for moving the character.
 #[derive(Component, Debug, Clone, Default, Reflect)]
 #[reflect(Component, Default)]
@@ -72,12 +230,14 @@ pub struct MovementForce {
 }
 
 /// Calculates the movement forces for this controller.
-pub fn movement_force(
-    ctx: Res<RapierContext>,
+#[allow(clippy::type_complexity)]
+pub fn movement_force<B: WanderlustBackend>(
+    backend: StaticSystemParam<B::SpatialQuery>,
     mut query: Query<(
         Entity,
         &mut MovementForce,
         &mut Movement,
+        &FrictionSettings,
         &Gravity,
         &ControllerInput,
         &GroundCast,
@@ -85,17 +245,18 @@ pub fn movement_force(
         &ViableGroundCast,
         &ControllerVelocity,
         &ControllerMass,
+        Option<&WallRun>,
+        Option<&AirControl>,
     )>,
     globals: Query<&GlobalTransform>,
-    masses: Query<&ReadMassProperties>,
-    frictions: Query<&Friction>,
     //mut gizmos: Gizmos,
 ) {
-    let dt = ctx.integration_parameters.dt;
+    let dt = B::dt(&backend);
     for (
         controller_entity,
         mut force,
         movement,
+        friction_settings,
         gravity,
         input,
         ground,
@@ -103,6 +264,8 @@ pub fn movement_force(
         viable_ground,
         velocity,
         mass,
+        wall_run,
+        air_control,
     ) in &mut query
     {
         force.linear = Vec3::ZERO;
@@ -110,8 +273,31 @@ pub fn movement_force(
         let grounded = **grounded;
         let force_scale = movement.force_scale(&gravity);
 
+        let surface = match viable_ground.current() {
+            Some(ground) if grounded => ground.surface,
+            _ => SurfaceProperties::default(),
+        };
+
+        let (max_speed, acceleration_multiplier, preserve_momentum) = match air_control {
+            Some(air_control) if !grounded => (
+                air_control.max_speed.unwrap_or(movement.max_speed),
+                air_control.acceleration_multiplier,
+                air_control.preserve_momentum,
+            ),
+            _ => (movement.max_speed, surface.acceleration_multiplier, false),
+        };
+
         let input_dir = input.movement.clamp_length_max(1.0);
-        let mut goal_vel = input_dir * movement.max_speed;
+        let mut goal_vel = input_dir * max_speed * surface.speed_multiplier;
+
+        // Run along the wall rather than into it, and hold on to it.
+        let wall_stick = match wall_run {
+            Some(wall_run) if wall_run.running => {
+                goal_vel = wall_run.direction * goal_vel.length();
+                -wall_run.wall_normal * wall_run.stick_force * mass.mass
+            }
+            _ => Vec3::ZERO,
+        };
 
         let slip_vector = match ground.current() {
             Some(ground) if !ground.stable => {
@@ -138,46 +324,55 @@ pub fn movement_force(
                 .get(ground.entity)
                 .unwrap_or(&GlobalTransform::IDENTITY);
 
-            let ground_mass = if let Ok(mass) = masses.get(ground.entity) {
-                (**mass).clone()
-            } else {
-                MassProperties::default()
-            };
+            let ground_mass = B::mass(&backend, ground.entity);
 
-            let com = ground_global.transform_point(ground_mass.local_center_of_mass);
+            let com = ground_global.transform_point(ground_mass.com);
             let projected_angular = ground.angular_velocity.project_onto(gravity.up_vector);
-            ground.linear_velocity + projected_angular.cross(ground.cast.point - com)
+            ground.linear_velocity
+                + projected_angular.cross(ground.cast.point - com)
+                + surface.conveyor_velocity
         } else {
             Vec3::ZERO
         };
 
         let relative_velocity = (velocity.linear - last_ground_vel) * force_scale;
-        let friction_coefficient = match viable_ground.current() {
+        let (friction_coefficient, friction_strength) = match viable_ground.current() {
             Some(ground) if grounded => {
-                let friction = frictions
-                    .get(controller_entity)
-                    .copied()
-                    .unwrap_or(Friction::default());
-                let ground_friction = frictions
-                    .get(ground.entity)
-                    .copied()
-                    .unwrap_or(Friction::default());
-                let friction_coefficient = friction.coefficient.max(ground_friction.coefficient);
-                friction_coefficient
-            }
-            _ => {
-                // Air damping coefficient
-                0.25
+                let friction = B::friction(&backend, controller_entity);
+                let ground_friction = B::friction(&backend, ground.entity);
+                let friction_coefficient = friction_settings
+                    .combine_rule
+                    .combine(friction, ground_friction);
+                (
+                    friction_coefficient * (1.0 - surface.slipperiness.clamp(0.0, 1.0)),
+                    friction_settings.ground_strength,
+                )
             }
+            _ => (
+                friction_settings.air_coefficient,
+                friction_settings.air_strength,
+            ),
         };
 
-        let strength = movement.acceleration.get(mass.mass, dt);
-        let movement_force = goal_vel * strength * force_scale;
+        let profile = movement.profile(grounded);
+        let speed_fraction = if max_speed > 0.0 {
+            relative_velocity.length() / max_speed
+        } else {
+            0.0
+        };
 
         let mut friction_velocity = relative_velocity;
         let goal_dir = goal_vel.normalize_or_zero();
         let goal_align = relative_velocity.dot(goal_dir);
 
+        let curve = if goal_align < 0.0 {
+            &profile.turn_around
+        } else {
+            &profile.acceleration
+        };
+        let strength = curve.get(speed_fraction, mass.mass, dt) * acceleration_multiplier;
+        let movement_force = goal_vel * strength * force_scale;
+
         let difference = (goal_vel.length() - goal_align.max(0.0)).max(0.0);
         let displacement = difference * goal_dir;
 
@@ -188,8 +383,25 @@ pub fn movement_force(
         let friction_offset = friction_align.clamp(0.0, goal_vel.length());
         friction_velocity -= friction_offset * goal_dir;
 
-        let friction_strength = Strength::Scaled(friction_coefficient.clamp(0.0, 1.0) * 45.0);
-        let friction_force = friction_velocity * friction_strength.get(mass.mass, dt) * force_scale;
+        let friction_strength =
+            Strength::Scaled(friction_coefficient.clamp(0.0, 1.0) * friction_strength);
+        if preserve_momentum {
+            friction_velocity = Vec3::ZERO;
+        }
+        let friction_force = friction_velocity
+            * friction_strength.get(mass.mass, dt)
+            * force_scale
+            * friction_settings.axis_scale;
+
+        // Brake towards the ground's velocity without input, on top of friction but never
+        // past it.
+        let deceleration_force = if goal_dir == Vec3::ZERO && !preserve_momentum {
+            let max_deceleration_force = relative_velocity * mass.mass / dt - friction_force;
+            (relative_velocity * profile.deceleration.get(speed_fraction, mass.mass, dt))
+                .clamp_length_max(max_deceleration_force.length())
+        } else {
+            Vec3::ZERO
+        };
 
         /*
         let squish = 0.2;
@@ -200,7 +412,8 @@ pub fn movement_force(
         gizmos.ray(Vec3::new(0.0, 0.1, 0.0), friction_velocity * squish, Color::CYAN);
         */
 
-        force.linear += movement_force - friction_force - slip_force;
+        force.linear +=
+            movement_force - friction_force - deceleration_force - slip_force + wall_stick;
     }
 }
 
@@ -220,6 +433,8 @@ pub struct Jump {
     pub jump_duration: f32,
     /// Timer for tracking `jump_duration`.
     pub jump_timer: f32,
+    /// [`SurfaceProperties::jump_multiplier`] of the ground the current jump started from.
+    pub surface_multiplier: f32,
     /// A function taking the current progress of a jump, from 0.0 to 1.0, with 0.0 indicating a jump has just begun and 1.0 indicating the jump has ended,
     /// which returns a modifier (usually from 0.0 to 1.0, but not necessarily) to multiply [`jump_force`](ControllerSettings::jump_force) by.
     #[reflect(ignore)]
@@ -233,6 +448,8 @@ pub struct Jump {
     pub pressed_last_frame: bool,
     /// The amount of force to apply downwards when the jump control is released prior to a jump expiring.
     /// This allows analog jumping by cutting the jump short when the control is released.
+    ///
+    /// Not used with [`GravityModifiers`], see [`GravityModifiers::release_multiplier`].
     pub stop_force: f32,
 
     /// A timer to track jump buffering. See [`jump_buffer_duration`](ControllerSettings::jump_buffer_duration)
@@ -253,6 +470,14 @@ pub struct Jump {
     /// How long to skip ground checks after jumping. Usually this should be set just high enough that the character is out of range of the ground
     /// just before the timer elapses.
     pub skip_ground_check_duration: f32,
+
+    /// How far above the collider to look for ceilings while jumping. Bumping into one ends the jump.
+    pub ceiling_cast_length: f32,
+    /// Largest angle, in radians, between a surface's normal and the down vector for it to
+    /// count as a ceiling.
+    pub max_ceiling_angle: f32,
+    /// Cancel upward velocity when bumping into a ceiling, instead of sliding along it.
+    pub stop_on_ceiling: bool,
 }
 
 impl Default for Jump {
@@ -264,6 +489,7 @@ impl Default for Jump {
             cooldown_timer: 0.0,
             jump_duration: 0.1,
             jump_timer: 0.0,
+            surface_multiplier: 1.0,
             decay_function: Some(|x| (1.0 - x).sqrt()),
             stop_force: 0.3,
 
@@ -279,6 +505,10 @@ impl Default for Jump {
             pressed_last_frame: false,
 
             skip_ground_check_duration: 0.0,
+
+            ceiling_cast_length: 0.1,
+            max_ceiling_angle: 75.0 * (std::f32::consts::PI / 180.0),
+            stop_on_ceiling: true,
         }
     }
 }
@@ -295,7 +525,7 @@ impl Jump {
         tick(&mut self.cooldown_timer);
         tick(&mut self.jump_timer);
         tick(&mut self.buffer_timer);
-        tick(&mut self.coyote_duration);
+        tick(&mut self.coyote_timer);
     }
 
     /// Are we currently jumping?
@@ -337,6 +567,135 @@ impl Jump {
     }
 }
 
+/// Describes jumps by how high they go and how long they take, in world units, rather
+/// than by forces.
+///
+/// The jump starts with the speed needed to reach `max_height` in `time_to_apex`, and
+/// gravity is overridden while rising so it gets there. Releasing jump early raises
+/// gravity so a tap still reaches `min_height`. Once the controller starts falling
+/// [`Gravity`] applies as usual.
+///
+/// This replaces [`Jump::initial_force`], [`Jump::force`], [`Jump::jump_duration`] and
+/// [`Jump::decay_function`], the rest of [`Jump`] still applies. This is opt-in, add
+/// it next to the [`Controller`] bundle to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct JumpProfile {
+    /// Apex height, above where the jump started, when jump is only tapped.
+    ///
+    /// This must be no greater than `max_height`, larger values are clamped to it.
+    pub min_height: f32,
+    /// Apex height, above where the jump started, when jump is held.
+    pub max_height: f32,
+    /// Seconds it takes to reach `max_height` when jump is held.
+    ///
+    /// This must be greater than zero, smaller values are clamped to [`f32::EPSILON`].
+    pub time_to_apex: f32,
+}
+
+impl Default for JumpProfile {
+    fn default() -> Self {
+        Self {
+            min_height: 0.5,
+            max_height: 1.5,
+            time_to_apex: 0.4,
+        }
+    }
+}
+
+impl JumpProfile {
+    /// Speed along the up vector a jump starts with.
+    pub fn initial_speed(&self) -> f32 {
+        2.0 * self.max_height / self.apex_time()
+    }
+
+    /// Gravitational acceleration along the up vector while rising with jump held.
+    pub fn rise_acceleration(&self) -> f32 {
+        -2.0 * self.max_height / self.apex_time().powi(2)
+    }
+
+    /// Gravitational acceleration along the up vector while rising after jump was released.
+    pub fn release_acceleration(&self) -> f32 {
+        let min_height = self.min_height.min(self.max_height).max(f32::EPSILON);
+        -self.initial_speed().powi(2) / (2.0 * min_height)
+    }
+
+    /// Multiplier for [`Gravity::acceleration`] while rising with jump held.
+    pub fn gravity_multiplier(&self, gravity: &Gravity) -> f32 {
+        self.rise_acceleration() / gravity.acceleration
+    }
+
+    /// Force, on top of cancelling the current vertical velocity, to apply on the first
+    /// frame of a jump.
+    pub fn initial_force(&self, mass: &ControllerMass, dt: f32) -> f32 {
+        self.initial_speed() * mass.mass / dt
+    }
+
+    /// [`JumpProfile::time_to_apex`], kept away from zero.
+    fn apex_time(&self) -> f32 {
+        self.time_to_apex.max(f32::EPSILON)
+    }
+}
+
+/// Lets the controller jump off walls found by the [`WallCaster`] while airborne.
+///
+/// Wall jumps launch the controller away from the wall rather than straight up, and
+/// have their own force, cooldown and number of jumps separate from [`Jump`].
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct WallJump {
+    /// The amount of force to apply on the first frame of a wall jump.
+    pub initial_force: f32,
+    /// How much to push away from the wall rather than up, from 0.0 (straight up along
+    /// [`Gravity::up_vector`]) to 1.0 (straight along the wall normal).
+    pub wall_blend: f32,
+    /// How long to wait before we can wall jump again.
+    pub cooldown_duration: f32,
+    /// Timer for tracking `cooldown_duration`.
+    pub cooldown_timer: f32,
+    /// Number of wall jumps we can make before we have to touch the ground again.
+    pub jumps: u32,
+    /// Remaining wall jumps before we have to touch the ground again.
+    pub remaining_jumps: u32,
+    /// Refill `remaining_jumps` when touching a different wall than the last one jumped off.
+    pub refill_on_new_wall: bool,
+    /// How many of [`Jump::remaining_jumps`] a wall jump gives back, up to [`Jump::jumps`].
+    pub restore_jumps: u32,
+    /// Wall entity we last jumped off.
+    pub last_wall: Option<Entity>,
+}
+
+impl Default for WallJump {
+    fn default() -> Self {
+        Self {
+            initial_force: 30.0,
+            wall_blend: 0.5,
+            cooldown_duration: 0.25,
+            cooldown_timer: 0.0,
+            jumps: 1,
+            remaining_jumps: 1,
+            refill_on_new_wall: true,
+            restore_jumps: 0,
+            last_wall: None,
+        }
+    }
+}
+
+impl WallJump {
+    /// Can we wall jump right now?
+    pub fn can_jump(&self) -> bool {
+        self.cooldown_timer <= 0.0 && self.remaining_jumps > 0
+    }
+
+    /// Direction to launch in when jumping off a wall with `normal`.
+    pub fn direction(&self, normal: Vec3, up_vector: Vec3) -> Vec3 {
+        let normal = normal.reject_from(up_vector).normalize_or_zero();
+        up_vector
+            .lerp(normal, self.wall_blend.clamp(0.0, 1.0))
+            .normalize_or_zero()
+    }
+}
+
 /// Calculated force for controller jumping.
 #[derive(Component, Debug, Default, Reflect)]
 #[reflect(Component, Default)]
@@ -346,8 +705,13 @@ pub struct JumpForce {
 }
 
 /// Calculate the jump force for the controller.
-pub fn jump_force(
+///
+/// Controllers holding onto a ledge or mantling don't jump, see [`LedgeGrab`] and
+/// [`Mantle`].
+#[allow(clippy::type_complexity)]
+pub fn jump_force<B: WanderlustBackend>(
     mut query: Query<(
+        Entity,
         &mut JumpForce,
         &mut FloatForce,
         &mut GravityForce,
@@ -359,11 +723,20 @@ pub fn jump_force(
         &Gravity,
         &ControllerVelocity,
         &ControllerMass,
+        (
+            Option<(&mut WallJump, &WallCast)>,
+            Option<&JumpProfile>,
+            Option<&GravityModifiers>,
+            Option<&LedgeGrab>,
+            Option<&Mantle>,
+        ),
     )>,
-    ctx: Res<RapierContext>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+    mut jumped: EventWriter<Jumped>,
 ) {
-    let dt = ctx.integration_parameters.dt;
+    let dt = B::dt(&backend);
     for (
+        entity,
         mut force,
         mut float_force,
         mut gravity_force,
@@ -375,6 +748,7 @@ pub fn jump_force(
         gravity,
         velocity,
         mass,
+        (mut wall_jump, profile, gravity_modifiers, ledge_grab, mantle),
     ) in &mut query
     {
         force.linear = Vec3::ZERO;
@@ -382,6 +756,36 @@ pub fn jump_force(
         let grounded = **grounded;
         jumping.tick_timers(dt);
 
+        // Jumping while hanging climbs the ledge instead, and a jump that started a
+        // mantle has already been used up by it.
+        if ledge_grab.is_some_and(|ledge_grab| ledge_grab.state != LedgeState::None)
+            || mantle.is_some_and(|mantle| mantle.mantling())
+        {
+            jumping.pressed_last_frame = input.jumping;
+            continue;
+        }
+
+        // Wall to jump off, if we are able to.
+        let mut jump_wall = None;
+        if let Some((wall_jump, wall_cast)) = &mut wall_jump {
+            if wall_jump.cooldown_timer > 0.0 {
+                wall_jump.cooldown_timer = (wall_jump.cooldown_timer - dt).max(0.0);
+            }
+
+            if grounded {
+                wall_jump.remaining_jumps = wall_jump.jumps;
+                wall_jump.last_wall = None;
+            } else if let Some(wall) = wall_cast.current() {
+                if wall_jump.refill_on_new_wall && wall_jump.last_wall != Some(wall.entity) {
+                    wall_jump.remaining_jumps = wall_jump.jumps;
+                }
+
+                if wall_jump.can_jump() {
+                    jump_wall = Some((*wall, velocity.linear - wall.point_velocity));
+                }
+            }
+        }
+
         if grounded {
             jumping.coyote_timer = jumping.coyote_duration;
         }
@@ -404,10 +808,49 @@ pub fn jump_force(
             jumping.buffer_timer = jumping.buffer_duration;
         }
 
-        if jumping.can_jump(grounded) && just_jumped {
+        if let (Some((wall, relative_velocity)), Some((wall_jump, _)), true) =
+            (jump_wall, &mut wall_jump, jump_inputted)
+        {
+            // Only keep the velocity along the wall, so the launch is consistent.
+            let direction = wall_jump.direction(wall.cast.normal, gravity.up_vector);
+            let along_wall = relative_velocity
+                .reject_from(gravity.up_vector)
+                .reject_from(wall.cast.normal);
+            let negate_velocity = (along_wall - relative_velocity) * mass.mass / dt;
+            force.linear += negate_velocity + direction * wall_jump.initial_force;
+
+            gravity_force.linear = Vec3::ZERO;
+            float_force.linear = Vec3::ZERO;
+
+            wall_jump.remaining_jumps = wall_jump.remaining_jumps.saturating_sub(1);
+            wall_jump.cooldown_timer = wall_jump.cooldown_duration;
+            wall_jump.last_wall = Some(wall.entity);
+            jumping.remaining_jumps =
+                (jumping.remaining_jumps + wall_jump.restore_jumps).min(jumping.jumps);
+            jumping.buffer_timer = 0.0;
+
+            jumping.surface_multiplier = 1.0;
+            jumping.jump_timer = jumping.jump_duration;
+            jumped.send(Jumped {
+                entity,
+                jump_index: wall_jump.jumps - wall_jump.remaining_jumps,
+                wall: Some(wall.entity),
+            });
+        } else if jumping.can_jump(grounded) && just_jumped {
             // Negating the current velocity increases consistency for falling jumps,
             // and prevents stacking jumps to reach high upwards velocities
-            let initial_jump_force = jumping.initial_force * gravity.up_vector;
+            // Only jumps off the ground, including coyote jumps, get the surface's boost.
+            jumping.surface_multiplier = match viable_ground.last() {
+                Some(ground) if jumping.remaining_jumps == jumping.jumps => {
+                    ground.surface.jump_multiplier
+                }
+                _ => 1.0,
+            };
+            let initial_force = match profile {
+                Some(profile) => profile.initial_force(mass, dt),
+                None => jumping.initial_force,
+            };
+            let initial_jump_force = initial_force * jumping.surface_multiplier * gravity.up_vector;
             let negate_up_velocity =
                 (-1.0 * gravity.up_vector * velocity.dot(gravity.up_vector)) * mass.mass / dt;
             force.linear += negate_up_velocity + initial_jump_force;
@@ -418,18 +861,46 @@ pub fn jump_force(
             jumping.remaining_jumps = jumping.remaining_jumps.saturating_sub(1);
             jumping.cooldown_timer = jumping.cooldown_duration;
 
-            jumping.jump_timer = jumping.jump_duration;
+            jumping.jump_timer = match profile {
+                Some(profile) => profile.time_to_apex.max(0.0),
+                None => jumping.jump_duration,
+            };
+            jumped.send(Jumped {
+                entity,
+                jump_index: jumping.jumps - jumping.remaining_jumps,
+                wall: None,
+            });
         // don't double up on initial force and jumping forces.
+        } else if let (Some(profile), true) = (profile, jumping.jumping()) {
+            if velocity.dot(gravity.up_vector) <= 0.0 {
+                // Reached the apex, fall as usual from here.
+                jumping.jump_timer = 0.0;
+            } else {
+                let acceleration = if input.jumping {
+                    ground_caster.skip_ground_check_timer = jumping.skip_ground_check_duration;
+                    profile.rise_acceleration()
+                } else {
+                    profile.release_acceleration()
+                };
+                gravity_force.linear = gravity.up_vector * mass.mass * acceleration;
+                float_force.linear = Vec3::ZERO;
+            }
         } else if jumping.jumping() {
             if !input.jumping {
-                // Cut the jump short if we aren't holding the jump down.
+                // Cut the jump short if we aren't holding the jump down, unless
+                // `GravityModifiers::release_multiplier` does that instead.
                 //jumping.reset_jump();
-                let stop_force = velocity.project_onto(gravity.up_vector) * -jumping.stop_force;
-                force.linear += stop_force;
+                if gravity_modifiers.is_none() {
+                    let stop_force = velocity.project_onto(gravity.up_vector) * -jumping.stop_force;
+                    force.linear += stop_force;
+                }
             } else {
                 ground_caster.skip_ground_check_timer = jumping.skip_ground_check_duration;
 
-                let jump = gravity.up_vector * jumping.force * jumping.decay_multiplier();
+                let jump = gravity.up_vector
+                    * jumping.force
+                    * jumping.surface_multiplier
+                    * jumping.decay_multiplier();
                 force.linear += jump;
             }
         }
@@ -437,3 +908,90 @@ pub fn jump_force(
         jumping.pressed_last_frame = input.jumping;
     }
 }
+
+/// Sent when a jumping controller bumps its head on a ceiling.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HeadBump {
+    /// Controller that bumped its head.
+    pub entity: Entity,
+    /// Entity the ceiling belongs to.
+    pub ceiling: Entity,
+    /// Upward speed of the controller, relative to the ceiling, when it was hit.
+    pub speed: f32,
+}
+
+/// End jumps early when the controller bumps into a ceiling, so it doesn't keep pushing
+/// into it for the rest of [`Jump::jump_duration`].
+///
+/// Ceilings are found with the same exclusions as [`GroundCaster::exclude_from_ground`].
+#[allow(clippy::type_complexity)]
+pub fn head_bump<B: WanderlustBackend>(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut Jump,
+        &mut JumpForce,
+        &GroundCaster,
+        &Gravity,
+        &ControllerVelocity,
+        &ControllerMass,
+    )>,
+    globals: Query<&GlobalTransform>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+    mut bumps: EventWriter<HeadBump>,
+) {
+    let dt = B::dt(&backend);
+    if dt == 0.0 {
+        return;
+    }
+
+    for (entity, global, mut jump, mut force, caster, gravity, velocity, mass) in &mut query {
+        if !jump.jumping() {
+            continue;
+        }
+
+        let Some(shape) = B::collider_shape(&backend, entity) else {
+            continue;
+        };
+
+        let up_vector = gravity.up_vector;
+        let predicate =
+            |collider| collider != entity && !caster.exclude_from_ground.contains(&collider);
+        let (_, rotation, position) = global.to_scale_rotation_translation();
+
+        // Start slightly below so a collider already touching the ceiling still hits it.
+        let Some((hit, cast)) = B::cast_shape(
+            &backend,
+            &shape,
+            position - up_vector * FUDGE,
+            rotation,
+            up_vector,
+            jump.ceiling_cast_length + FUDGE,
+            CastFilter::new().exclude_sensors().predicate(&predicate),
+        ) else {
+            continue;
+        };
+
+        // Anything shallower is a wall or something to land on.
+        if cast.normal.angle_between(-up_vector) > jump.max_ceiling_angle {
+            continue;
+        }
+
+        let ceiling = Wall::from_cast::<B>(hit, cast, up_vector, &backend, &globals);
+        let speed = (velocity.linear - ceiling.point_velocity).dot(up_vector);
+
+        jump.jump_timer = 0.0;
+        if jump.stop_on_ceiling && speed > 0.0 {
+            // Replaces any jump force from this frame.
+            force.linear = -up_vector * speed * mass.mass / dt;
+        } else {
+            force.linear = force.linear.reject_from(up_vector);
+        }
+
+        bumps.send(HeadBump {
+            entity,
+            ceiling: ceiling.entity,
+            speed,
+        });
+    }
+}
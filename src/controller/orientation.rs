@@ -35,6 +35,28 @@ impl Default for Float {
     }
 }
 
+/// Pull the controller down onto the ground while moving, so it hugs terrain when
+/// running down slopes or off small ledges instead of briefly leaving the ground.
+///
+/// This is opt-in, add it next to the [`Controller`] bundle to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct GroundSnap {
+    /// How far beyond [`Float::distance`] the ground can be while still being snapped to.
+    pub max_snap_distance: f32,
+    /// Minimum speed along the ground, relative to the ground, before snapping.
+    pub min_speed: f32,
+}
+
+impl Default for GroundSnap {
+    fn default() -> Self {
+        Self {
+            max_snap_distance: 0.5,
+            min_speed: 0.1,
+        }
+    }
+}
+
 /// Force applied to push the controller off the ground.
 #[derive(Component, Default, Reflect)]
 #[reflect(Component, Default)]
@@ -44,6 +66,7 @@ pub struct FloatForce {
 }
 
 /// Calculate "floating" force, as seen [here](https://www.youtube.com/watch?v=qdskE8PJy6Q)
+#[allow(clippy::type_complexity)]
 pub fn float_force(
     mut query: Query<(
         &GlobalTransform,
@@ -53,9 +76,12 @@ pub fn float_force(
         &ControllerVelocity,
         &ControllerMass,
         &Gravity,
+        Option<&GroundSnap>,
+        Option<&Jump>,
     )>,
 ) {
-    for (global, mut force, float, viable_ground, velocity, mass, gravity) in &mut query {
+    for (global, mut force, float, viable_ground, velocity, mass, gravity, snap, jump) in &mut query
+    {
         force.linear = Vec3::ZERO;
 
         let Some(ground) = viable_ground.current() else {
@@ -76,7 +102,19 @@ pub fn float_force(
         let displacement = float.distance - worldspace_diff;
         //info!("displacement: {:.2?}", displacement);
 
-        if displacement > 0.0 {
+        // Jumps are allowed to leave the ground.
+        let jumped = jump.is_some_and(|jump| jump.jumping() || jump.remaining_jumps < jump.jumps);
+        let snapping = match snap {
+            Some(snap) if !jumped => {
+                let speed = (controller_point_velocity - ground.point_velocity)
+                    .reject_from(up_vector)
+                    .length();
+                speed >= snap.min_speed && -displacement <= snap.max_snap_distance
+            }
+            _ => false,
+        };
+
+        if displacement > 0.0 || snapping {
             let strength = displacement * float.spring.strength.get(Vec3::splat(mass.mass));
             let damping = relative_velocity * float.spring.damp_coefficient(Vec3::splat(mass.mass));
             force.linear += up_vector * (strength - damping);
@@ -169,3 +207,96 @@ pub fn upright_force(
         };
     }
 }
+
+/// Turns the controller towards a new [`Gravity::up_vector`] gradually rather than
+/// all at once.
+///
+/// Whenever something else changes [`Gravity::up_vector`], such as a
+/// [`GravityField`], that becomes the `target`, and the up vector every other system
+/// sees is rotated towards it at a fixed rate. [`Upright::forward_vector`] is rotated
+/// along with it, so the controller keeps facing the same way relative to the ground.
+///
+/// This is opt-in, add it next to the [`Controller`] bundle to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct UpReorientation {
+    /// Seconds it takes to turn all the way around, for a gravity flip. Smaller changes
+    /// take proportionally less time.
+    pub duration: f32,
+    /// Up vector being turned towards.
+    pub target: Vec3,
+    /// Up vector that was last written to [`Gravity::up_vector`], zero before the first update.
+    pub current: Vec3,
+}
+
+impl Default for UpReorientation {
+    fn default() -> Self {
+        Self {
+            duration: 0.5,
+            target: Vec3::ZERO,
+            current: Vec3::ZERO,
+        }
+    }
+}
+
+impl UpReorientation {
+    /// Is the up vector still turning towards the target.
+    pub fn reorienting(&self) -> bool {
+        self.current != self.target
+    }
+}
+
+/// Rotate [`Gravity::up_vector`] towards [`UpReorientation::target`].
+pub fn reorient_up_vector<B: WanderlustBackend>(
+    mut query: Query<(&mut UpReorientation, &mut Gravity, Option<&mut Upright>)>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+) {
+    let dt = B::dt(&backend);
+    for (mut reorientation, mut gravity, upright) in &mut query {
+        let up_vector = gravity.up_vector.normalize_or_zero();
+        if up_vector == Vec3::ZERO {
+            continue;
+        }
+
+        // Someone other than us changed the up vector.
+        if gravity.up_vector != reorientation.current {
+            reorientation.target = up_vector;
+        }
+        if reorientation.current == Vec3::ZERO {
+            reorientation.current = reorientation.target;
+        }
+
+        let current = reorientation.current;
+        let target = reorientation.target;
+        let angle = current.angle_between(target);
+        let max_angle = if reorientation.duration > 0.0 {
+            std::f32::consts::PI / reorientation.duration * dt
+        } else {
+            f32::INFINITY
+        };
+
+        if current != target {
+            let (rotation, next) = if angle <= max_angle {
+                (Quat::from_rotation_arc(current, target), target)
+            } else {
+                let rotation = Quat::IDENTITY
+                    .slerp(Quat::from_rotation_arc(current, target), max_angle / angle);
+                (rotation, (rotation * current).normalize())
+            };
+
+            if let Some(mut upright) = upright {
+                if let Some(forward) = upright.forward_vector {
+                    let forward = (rotation * forward).reject_from(next).normalize_or_zero();
+                    upright.forward_vector = (forward != Vec3::ZERO).then_some(forward);
+                }
+            }
+
+            reorientation.current = next;
+        }
+
+        // Only write when needed so `Changed<Gravity>` stays meaningful.
+        if gravity.up_vector != reorientation.current {
+            gravity.up_vector = reorientation.current;
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::controller::*;
+use std::marker::PhantomData;
+
+/// How the controller should climb steps in front of it.
+///
+/// Steps shorter than the gap between the bottom of the collider and the ground are
+/// handled by the [`Float`] spring alone. Taller steps block the collider, so the
+/// controller probes ahead of its movement and hops up onto steps that are low enough
+/// and deep enough to stand on. Add this component to a controller to enable it.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct StepUp {
+    /// Tallest step, measured from the current ground, the controller will climb.
+    pub max_step_height: f32,
+    /// How much of the top of the step needs to be free to stand on for it to be climbed.
+    pub min_step_depth: f32,
+    /// How far ahead of the collider to look for steps, in addition to the distance
+    /// travelled this frame.
+    pub probe_distance: f32,
+    /// Is the controller currently climbing a step.
+    pub stepping: bool,
+}
+
+impl Default for StepUp {
+    fn default() -> Self {
+        Self {
+            max_step_height: 0.4,
+            min_step_depth: 0.1,
+            probe_distance: 0.3,
+            stepping: false,
+        }
+    }
+}
+
+/// Calculated force for climbing steps.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct StepUpForce {
+    /// Linear impulse to lift the character onto the step.
+    pub linear: Vec3,
+}
+
+/// Sent when a controller starts climbing a step.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SteppedUp {
+    /// Controller climbing the step.
+    pub entity: Entity,
+    /// Entity the step belongs to.
+    pub step: Entity,
+    /// Height of the step above the ground the controller was standing on.
+    pub height: f32,
+}
+
+/// A step found in front of the controller.
+#[derive(Debug, Clone, Copy)]
+pub struct Step {
+    /// Collider the step belongs to.
+    pub entity: Entity,
+    /// Top surface of the step.
+    pub cast: CastResult,
+    /// Height of the step above the current ground.
+    pub height: f32,
+}
+
+impl<'c, 'f, B: WanderlustBackend> GroundCastParams<'c, 'f, B> {
+    /// Probe for a climbable step along `direction`, up to `max_toi` away.
+    ///
+    /// The top of the step must be at most `max_height` above `ground`, with at least
+    /// `min_depth` of it free to stand on. `direction` should be normalized and
+    /// perpendicular to `up_vector`.
+    pub fn probe_step(
+        &self,
+        ctx: &BackendQuery<B>,
+        max_height: f32,
+        min_depth: f32,
+        ground: &Ground,
+        max_ground_angle: f32,
+        up_vector: Vec3,
+    ) -> Option<Step> {
+        // Start slightly behind so a collider already touching the step still hits it.
+        let (_, riser) = B::cast_shape(
+            ctx,
+            self.shape,
+            self.position - self.direction * FUDGE,
+            self.rotation,
+            self.direction,
+            self.max_toi + FUDGE,
+            self.filter,
+        )?;
+
+        // Walkable slopes are left to the float spring.
+        if riser.viable(up_vector, max_ground_angle) {
+            return None;
+        }
+
+        // Look down onto the top of the step from just above the tallest step we accept.
+        let ground_height = ground.cast.point.dot(up_vector);
+        let riser_height = riser.point.dot(up_vector);
+        let over = riser.point
+            + self.direction * min_depth
+            + up_vector * (ground_height + max_height + FUDGE - riser_height);
+        let (entity, top) = B::cast_ray(ctx, over, -up_vector, max_height + FUDGE, self.filter)?;
+
+        // Starting inside the obstacle means it is taller than `max_height`.
+        if top.toi <= 0.0 || !top.viable(up_vector, max_ground_angle) {
+            return None;
+        }
+
+        let height = top.point.dot(up_vector) - ground_height;
+        if height <= FUDGE || height > max_height {
+            return None;
+        }
+
+        // Make sure the collider fits on top of the step.
+        let raised = self.position + up_vector * (height + FUDGE);
+        let needed = self.direction.dot(top.point - self.position).max(0.0);
+        if let Some((_, blocked)) = B::cast_shape(
+            ctx,
+            self.shape,
+            raised,
+            self.rotation,
+            self.direction,
+            needed,
+            self.filter,
+        ) {
+            if blocked.toi < needed {
+                return None;
+            }
+        }
+
+        Some(Step {
+            entity,
+            cast: top,
+            height,
+        })
+    }
+}
+
+/// Calculate the force for hopping up onto steps in front of the controller.
+#[allow(clippy::type_complexity)]
+pub fn step_up_force<B: WanderlustBackend>(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut StepUpForce,
+        Option<&mut StepUp>,
+        &ControllerInput,
+        &GroundCaster,
+        &ViableGroundCast,
+        &Grounded,
+        &Jump,
+        &Float,
+        &Gravity,
+        &ControllerVelocity,
+        &ControllerMass,
+    )>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+    mut stepped: EventWriter<SteppedUp>,
+) {
+    let dt = B::dt(&backend);
+    if dt == 0.0 {
+        return;
+    }
+
+    for (
+        entity,
+        global,
+        mut force,
+        step_up,
+        input,
+        caster,
+        viable_ground,
+        grounded,
+        jump,
+        float,
+        gravity,
+        velocity,
+        mass,
+    ) in &mut query
+    {
+        force.linear = Vec3::ZERO;
+        let Some(mut step_up) = step_up else {
+            continue;
+        };
+
+        let up_vector = gravity.up_vector;
+        let ground = match viable_ground.current() {
+            Some(ground) if **grounded && !jump.jumping() => ground,
+            _ => {
+                step_up.stepping = false;
+                continue;
+            }
+        };
+
+        let direction = input.movement.reject_from(up_vector).normalize_or_zero();
+        if direction == Vec3::ZERO {
+            step_up.stepping = false;
+            continue;
+        }
+        let Some(shape) = B::collider_shape(&backend, entity) else {
+            continue;
+        };
+
+        let relative_velocity = velocity.linear - ground.point_velocity;
+        let distance = step_up.probe_distance + relative_velocity.dot(direction).max(0.0) * dt;
+
+        let predicate =
+            |collider| collider != entity && !caster.exclude_from_ground.contains(&collider);
+        let (_, rotation, position) = global.to_scale_rotation_translation();
+        let params = GroundCastParams::<B> {
+            position,
+            rotation,
+            direction,
+            shape: &shape,
+            max_toi: distance,
+            filter: CastFilter::new().exclude_sensors().predicate(&predicate),
+            backend: PhantomData,
+        };
+
+        let Some(step) = params.probe_step(
+            &backend,
+            step_up.max_step_height,
+            step_up.min_step_depth,
+            ground,
+            caster.max_ground_angle,
+            up_vector,
+        ) else {
+            step_up.stepping = false;
+            continue;
+        };
+
+        // Hop just high enough to float at `Float::distance` above the step.
+        let rise = step.cast.point.dot(up_vector) + float.distance - position.dot(up_vector);
+        let needed_velocity = (2.0 * gravity.acceleration.abs() * rise.max(0.0)).sqrt();
+        let up_velocity = relative_velocity.dot(up_vector);
+        if needed_velocity > up_velocity {
+            force.linear = up_vector * (needed_velocity - up_velocity) * mass.mass / dt;
+        }
+
+        if !step_up.stepping {
+            step_up.stepping = true;
+            stepped.send(SteppedUp {
+                entity,
+                step: B::collider_body(&backend, step.entity),
+                height: step.height,
+            });
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::controller::*;
+
+/// How a ground entity affects controllers standing on it.
+///
+/// This can be added to any collider, or to the rigid body it belongs to, and is
+/// exposed on [`Ground::surface`]. Without it the ground uses
+/// [`SurfaceProperties::default`], which doesn't change anything.
+///
+/// For example ice is a high `slipperiness`, mud a low `speed_multiplier`, a trampoline
+/// a high `jump_multiplier` and a conveyor belt a `conveyor_velocity`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct SurfaceProperties {
+    /// Multiplier for [`Movement::max_speed`] while standing on this surface.
+    pub speed_multiplier: f32,
+    /// Multiplier for the [`Movement::ground`] acceleration and turn around strengths
+    /// while standing on this surface.
+    pub acceleration_multiplier: f32,
+    /// Multiplier for the [`Jump`] forces when jumping off of this surface.
+    pub jump_multiplier: f32,
+    /// How much of the friction is lost on this surface, from 0.0 (normal friction)
+    /// to 1.0 (no friction at all).
+    pub slipperiness: f32,
+    /// Velocity, in world space, that this surface carries controllers along at, as if
+    /// it were moving.
+    pub conveyor_velocity: Vec3,
+    /// Free for games to use, e.g. to pick footstep sounds.
+    pub tag: u32,
+}
+
+impl Default for SurfaceProperties {
+    fn default() -> Self {
+        Self {
+            speed_multiplier: 1.0,
+            acceleration_multiplier: 1.0,
+            jump_multiplier: 1.0,
+            slipperiness: 0.0,
+            conveyor_velocity: Vec3::ZERO,
+            tag: 0,
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::controller::*;
+use bevy::utils::HashSet;
+
+/// How to detect walls next to the controller.
+///
+/// Walls are looked for along the direction the controller is moving, as well as
+/// to either side of it. Controllers with a [`WallRun`], [`WallJump`] or [`LedgeGrab`]
+/// get a default caster and a [`WallCast`] added automatically, see [`add_wall_casters`].
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct WallCaster {
+    /// An offset to start the wall casts from, relative to the character's origin.
+    pub cast_origin: Vec3,
+    /// How far from the collider to look for walls.
+    pub cast_length: f32,
+    /// Set of entities that should be ignored when wall casting.
+    pub exclude_from_wall: HashSet<Entity>,
+    /// Smallest angle, in radians, between a surface normal and the up vector for the
+    /// surface to count as a wall.
+    pub min_wall_angle: f32,
+    /// Largest angle, in radians, between a surface normal and the up vector for the
+    /// surface to count as a wall. Anything steeper is considered a ceiling.
+    pub max_wall_angle: f32,
+}
+
+impl Default for WallCaster {
+    fn default() -> Self {
+        Self {
+            cast_origin: Vec3::ZERO,
+            cast_length: 0.2,
+            exclude_from_wall: default(),
+            min_wall_angle: 75.0 * (std::f32::consts::PI / 180.0),
+            max_wall_angle: 105.0 * (std::f32::consts::PI / 180.0),
+        }
+    }
+}
+
+/// Information about a wall next to the controller.
+#[derive(Copy, Clone, Reflect)]
+pub struct Wall {
+    /// Entity found in wall cast.
+    pub entity: Entity,
+    /// Specifics of the wall contact.
+    pub cast: CastResult,
+    /// Direction of the cast that found this wall.
+    pub direction: Vec3,
+    /// Angular velocity of the wall body.
+    pub angular_velocity: Vec3,
+    /// Linear velocity of the wall body.
+    pub linear_velocity: Vec3,
+    /// Linear velocity at the point of contact.
+    pub point_velocity: Vec3,
+}
+
+impl Wall {
+    /// Construct a `Wall` from a cast hitting the collider `entity`.
+    pub fn from_cast<B: WanderlustBackend>(
+        entity: Entity,
+        cast: CastResult,
+        direction: Vec3,
+        backend: &BackendQuery<B>,
+        globals: &Query<&GlobalTransform>,
+    ) -> Self {
+        let wall_entity = B::collider_body(backend, entity);
+
+        let local_com = B::mass(backend, wall_entity).com;
+        let wall_velocity = B::velocity(backend, wall_entity);
+
+        let global = globals
+            .get(wall_entity)
+            .unwrap_or(&GlobalTransform::IDENTITY);
+        let com = global.transform_point(local_com);
+        let point_velocity = wall_velocity.linear + wall_velocity.angular.cross(cast.point - com);
+
+        Wall {
+            entity: wall_entity,
+            cast,
+            direction,
+            linear_velocity: wall_velocity.linear,
+            angular_velocity: wall_velocity.angular,
+            point_velocity,
+        }
+    }
+}
+
+/// Current/last wall.
+#[derive(Default, Reflect)]
+pub enum WallCache {
+    /// The wall we are currently next to.
+    Wall(Wall),
+    /// Cached wall.
+    Last(Wall),
+    /// No wall.
+    #[default]
+    None,
+}
+
+impl WallCache {
+    /// Update the wall depending on the current wall cast.
+    pub fn update(&mut self, wall: Option<Wall>) {
+        match wall {
+            Some(wall) => {
+                *self = Self::Wall(wall);
+            }
+            None => {
+                self.into_last();
+            }
+        }
+    }
+
+    /// Archive this wall cast.
+    pub fn into_last(&mut self) {
+        if let Self::Wall(wall) = self {
+            *self = Self::Last(*wall);
+        }
+    }
+
+    /// Wall we are currently touching.
+    pub fn current(&self) -> Option<&Wall> {
+        match self {
+            Self::Wall(wall) => Some(wall),
+            _ => None,
+        }
+    }
+
+    /// Last wall we touched, this includes the wall we are currently touching.
+    pub fn last(&self) -> Option<&Wall> {
+        match self {
+            Self::Wall(wall) | Self::Last(wall) => Some(wall),
+            Self::None => None,
+        }
+    }
+}
+
+/// The cached wall cast. Contains the entity hit, the hit info, and velocity of the entity
+/// hit.
+#[derive(Component, Default, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+pub struct WallCast(
+    /// Wall that was found this frame.
+    pub WallCache,
+);
+
+impl WallCast {
+    /// Is the controller currently next to a wall.
+    pub fn touching_wall(&self) -> bool {
+        self.current().is_some()
+    }
+}
+
+/// Adds a [`WallCaster`] and [`WallCast`] to controllers that need to look for walls.
+#[allow(clippy::type_complexity)]
+pub fn add_wall_casters(
+    mut commands: Commands,
+    query: Query<
+        (Entity, Option<&WallCaster>),
+        (
+            Without<WallCast>,
+            Or<(
+                With<WallCaster>,
+                With<WallRun>,
+                With<WallJump>,
+                With<LedgeGrab>,
+            )>,
+        ),
+    >,
+) {
+    for (entity, caster) in &query {
+        let mut entity = commands.entity(entity);
+        entity.insert(WallCast::default());
+        if caster.is_none() {
+            entity.insert(WallCaster::default());
+        }
+    }
+}
+
+/// Looks for walls around the controller and updates the [`WallCast`].
+pub fn find_walls<B: WanderlustBackend>(
+    mut casters: Query<(
+        Entity,
+        &GlobalTransform,
+        &Gravity,
+        &WallCaster,
+        &mut WallCast,
+        &ControllerInput,
+        &ControllerVelocity,
+    )>,
+    globals: Query<&GlobalTransform>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+) {
+    for (entity, tf, gravity, caster, mut wall, input, velocity) in &mut casters {
+        let up_vector = gravity.up_vector;
+
+        // Prefer where the controller wants to go, then where it is going, then the wall
+        // it was last touching so standing still against a wall keeps it.
+        let forward = [
+            input.movement,
+            velocity.linear,
+            wall.last()
+                .map(|wall| -wall.cast.normal)
+                .unwrap_or_default(),
+        ]
+        .into_iter()
+        .map(|direction| direction.reject_from(up_vector).normalize_or_zero())
+        .find(|direction| *direction != Vec3::ZERO);
+        let Some(forward) = forward else {
+            wall.into_last();
+            continue;
+        };
+        let side = up_vector.cross(forward).normalize_or_zero();
+
+        let Some(shape) = B::collider_shape(&backend, entity) else {
+            continue;
+        };
+
+        let predicate =
+            |collider| collider != entity && !caster.exclude_from_wall.contains(&collider);
+        let filter = CastFilter::new().exclude_sensors().predicate(&predicate);
+
+        let position = tf.transform_point(caster.cast_origin);
+        let rotation = tf.to_scale_rotation_translation().1;
+
+        let next_wall = [forward, side, -side]
+            .into_iter()
+            .filter_map(|direction| {
+                // Start slightly behind so a collider already touching the wall still hits it.
+                let (hit, mut cast) = B::cast_shape(
+                    &backend,
+                    &shape,
+                    position - direction * FUDGE,
+                    rotation,
+                    direction,
+                    caster.cast_length + FUDGE,
+                    filter,
+                )?;
+                cast.toi = (cast.toi - FUDGE).max(0.0);
+
+                let angle = cast.normal.angle_between(up_vector);
+                let is_wall = angle >= caster.min_wall_angle && angle <= caster.max_wall_angle;
+                is_wall.then_some((hit, cast, direction))
+            })
+            .min_by(|(_, a, _), (_, b, _)| a.toi.total_cmp(&b.toi))
+            .map(|(hit, cast, direction)| {
+                Wall::from_cast::<B>(hit, cast, direction, &backend, &globals)
+            });
+
+        wall.update(next_wall);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::controller::*;
+
+/// Lets the controller run along walls while airborne.
+///
+/// A wall run starts when the controller is off the ground, next to a wall found by the
+/// [`WallCaster`], moving along it fast enough and not steering away from it. While
+/// running, gravity is scaled down and [`Movement`] pushes along the wall instead of
+/// along the input direction.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct WallRun {
+    /// Minimum speed along the wall, relative to the wall, to start or keep running.
+    pub min_speed: f32,
+    /// Multiplier for gravity while running.
+    pub gravity_scale: f32,
+    /// Force per unit of mass pushing the controller into the wall so it doesn't drift off.
+    pub stick_force: f32,
+    /// How long a wall run can last, or `None` for as long as the conditions hold.
+    pub max_duration: Option<f32>,
+    /// Time left for the current wall run. Refilled when touching the ground.
+    pub timer: f32,
+    /// Is the controller currently running along a wall.
+    pub running: bool,
+    /// Direction along the wall the controller is running in.
+    pub direction: Vec3,
+    /// Normal of the wall the controller is running on.
+    pub wall_normal: Vec3,
+}
+
+impl Default for WallRun {
+    fn default() -> Self {
+        Self {
+            min_speed: 3.0,
+            gravity_scale: 0.2,
+            stick_force: 5.0,
+            max_duration: Some(1.5),
+            timer: 1.5,
+            running: false,
+            direction: Vec3::ZERO,
+            wall_normal: Vec3::ZERO,
+        }
+    }
+}
+
+impl WallRun {
+    /// Is there time left to wall run.
+    pub fn has_time(&self) -> bool {
+        self.max_duration.is_none() || self.timer > 0.0
+    }
+
+    /// Stop the current wall run.
+    pub fn stop(&mut self) {
+        self.running = false;
+        self.direction = Vec3::ZERO;
+        self.wall_normal = Vec3::ZERO;
+    }
+}
+
+/// Start, continue or stop wall runs, and reduce gravity while running.
+#[allow(clippy::type_complexity)]
+pub fn wall_run<B: WanderlustBackend>(
+    mut query: Query<(
+        &mut WallRun,
+        &mut GravityForce,
+        &WallCast,
+        &Grounded,
+        &Jump,
+        &ControllerInput,
+        &Gravity,
+        &ControllerVelocity,
+    )>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+) {
+    let dt = B::dt(&backend);
+    for (mut wall_run, mut gravity_force, wall, grounded, jump, input, gravity, velocity) in
+        &mut query
+    {
+        if **grounded {
+            wall_run.stop();
+            wall_run.timer = wall_run.max_duration.unwrap_or_default();
+            continue;
+        }
+
+        let up_vector = gravity.up_vector;
+        let Some(wall) = wall
+            .current()
+            .filter(|_| !jump.jumping() && wall_run.has_time())
+        else {
+            wall_run.stop();
+            continue;
+        };
+
+        let normal = wall.cast.normal.reject_from(up_vector).normalize_or_zero();
+        let tangent = normal.cross(up_vector).normalize_or_zero();
+        let relative_velocity = velocity.linear - wall.point_velocity;
+        let along = relative_velocity.dot(tangent);
+
+        let input_dir = input.movement.normalize_or_zero();
+        let holding = input_dir != Vec3::ZERO && input_dir.dot(normal) <= FUDGE;
+        if !holding || along.abs() < wall_run.min_speed {
+            wall_run.stop();
+            continue;
+        }
+
+        wall_run.running = true;
+        wall_run.direction = tangent * along.signum();
+        wall_run.wall_normal = normal;
+        if wall_run.max_duration.is_some() {
+            wall_run.timer = (wall_run.timer - dt).max(0.0);
+        }
+
+        gravity_force.linear *= wall_run.gravity_scale;
+    }
+}
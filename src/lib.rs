@@ -5,16 +5,41 @@
 )]
 #![doc = include_str!("../README.md")]
 
+#[cfg(any(
+    all(feature = "rapier", feature = "rapier2d"),
+    all(feature = "rapier", feature = "xpbd"),
+    all(feature = "rapier2d", feature = "xpbd"),
+))]
+compile_error!(
+    "the `rapier`, `rapier2d` and `xpbd` features are mutually exclusive, use `default-features = false` to pick a non-default backend"
+);
+
+mod backend;
 mod bundles;
 mod controller;
 mod physics;
 mod plugins;
 mod spring;
 
+#[cfg(feature = "analytic")]
+mod analytic;
 #[cfg(feature = "rapier")]
 mod rapier;
+#[cfg(feature = "rapier2d")]
+mod rapier2d;
+#[cfg(feature = "xpbd")]
+mod xpbd;
 
 pub use {
-    bundles::ControllerBundle, controller::*, physics::*, plugins::WanderlustPlugin, rapier::*,
+    backend::*, bundles::ControllerBundle, controller::*, physics::*, plugins::WanderlustPlugin,
     spring::*,
 };
+
+#[cfg(feature = "analytic")]
+pub use analytic::*;
+#[cfg(feature = "rapier")]
+pub use rapier::*;
+#[cfg(feature = "rapier2d")]
+pub use rapier2d::*;
+#[cfg(feature = "xpbd")]
+pub use xpbd::*;
@@ -1,4 +1,5 @@
-use bevy::prelude::*;
+use crate::{backend::*, controller::*};
+use bevy::{ecs::system::StaticSystemParam, prelude::*};
 
 /// Mass/inertia properties for controller.
 #[derive(Component, Clone, Default, Reflect)]
@@ -52,3 +53,65 @@ impl Default for ControllerPhysicsBundle {
         }
     }
 }
+
+/// Sync backend masses over to our masses.
+pub fn get_mass_from_backend<B: WanderlustBackend>(
+    mut query: Query<(Entity, &mut ControllerMass)>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+) {
+    for (entity, mut mass) in &mut query {
+        *mass = B::mass(&backend, entity);
+    }
+}
+
+/// Sync backend velocities over to our velocities.
+pub fn get_velocity_from_backend<B: WanderlustBackend>(
+    mut query: Query<(Entity, &mut ControllerVelocity)>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+) {
+    for (entity, mut velocity) in &mut query {
+        *velocity = B::velocity(&backend, entity);
+    }
+}
+
+/// Apply forces to the controller to make it float, move, jump, etc.
+pub fn apply_forces<B: WanderlustBackend>(
+    forces: Query<(Entity, &ControllerForce)>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+    mut impulses: StaticSystemParam<B::Impulses>,
+) {
+    let dt = B::dt(&backend);
+    for (entity, force) in &forces {
+        B::apply_impulse(&mut impulses, entity, force.linear * dt, force.angular * dt);
+    }
+}
+
+/// Apply the opposing ground force to the entity we are pushing off of to float.
+pub fn apply_ground_forces<B: WanderlustBackend>(
+    ground_forces: Query<(&GroundForce, &ViableGroundCast)>,
+    backend: StaticSystemParam<B::SpatialQuery>,
+    mut impulses: StaticSystemParam<B::Impulses>,
+) {
+    let dt = B::dt(&backend);
+    for (force, viable_ground) in &ground_forces {
+        if let Some(ground) = viable_ground.current() {
+            B::apply_impulse(
+                &mut impulses,
+                ground.entity,
+                force.linear * dt,
+                force.angular * dt,
+            );
+        }
+    }
+}
+
+/// Closest point to `point` on the segment `a`-`b`.
+pub(crate) fn closest_on_segment(a: Vec3, b: Vec3, point: Vec3) -> Vec3 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared <= f32::EPSILON {
+        return a;
+    }
+    let t = ((point - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    a + ab * t
+}
@@ -1,16 +1,29 @@
-use crate::controller::*;
+use crate::{backend::*, controller::*, physics::*};
 use bevy::{ecs::schedule::ScheduleLabel, prelude::*, utils::HashSet};
-use bevy_rapier3d::prelude::*;
+use std::marker::PhantomData;
 
 /// The [character controller](CharacterController) plugin. Necessary to have the character controller
 /// work.
-pub struct WanderlustPlugin {
+///
+/// The controller systems run on top of the physics backend `B`, see [`WanderlustBackend`].
+pub struct WanderlustPlugin<B: WanderlustBackend> {
     tweaks: bool,
     schedule: Box<dyn ScheduleLabel>,
     default_system_setup: bool,
+    backend: PhantomData<B>,
 }
 
-impl WanderlustPlugin {
+impl<B: WanderlustBackend> WanderlustPlugin<B> {
+    /// Create the plugin for the backend `B`.
+    pub fn new() -> Self {
+        Self {
+            tweaks: true,
+            schedule: Box::new(PostUpdate),
+            default_system_setup: true,
+            backend: PhantomData,
+        }
+    }
+
     /// Apply tweaks to rapier (`true`) to try to avoid some jitters/issues.
     pub fn with_tweaks(mut self, tweaks: bool) -> Self {
         self.tweaks = tweaks;
@@ -38,21 +51,39 @@ impl WanderlustPlugin {
     }
 }
 
-impl Default for WanderlustPlugin {
+#[cfg(feature = "rapier")]
+impl Default for WanderlustPlugin<crate::RapierBackend> {
     fn default() -> Self {
-        Self {
-            tweaks: true,
-            schedule: Box::new(PostUpdate),
-            default_system_setup: true,
-        }
+        Self::new()
+    }
+}
+
+#[cfg(feature = "rapier2d")]
+impl Default for WanderlustPlugin<crate::Rapier2dBackend> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "xpbd")]
+impl Default for WanderlustPlugin<crate::XpbdBackend> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Plugin for WanderlustPlugin {
+impl<B: WanderlustBackend> Plugin for WanderlustPlugin<B> {
     fn build(&self, app: &mut App) {
         app.register_type::<ControllerInput>()
             .register_type::<Gravity>()
             .register_type::<GravityForce>()
+            .register_type::<GravityModifiers>()
+            .register_type::<AirDrag>()
+            .register_type::<GravityField>()
+            .register_type::<GravityFieldReceiver>()
+            .register_type::<UpReorientation>()
+            .register_type::<Option<Gravity>>()
+            .register_type::<Vec<Entity>>()
             .register_type::<Movement>()
             .register_type::<MovementForce>()
             .register_type::<Float>()
@@ -65,16 +96,46 @@ impl Plugin for WanderlustPlugin {
             .register_type::<ViableGroundCast>()
             .register_type::<Grounded>()
             .register_type::<GroundForce>()
+            .register_type::<WallCaster>()
+            .register_type::<WallCast>()
+            .register_type::<WallRun>()
+            .register_type::<Option<f32>>()
             .register_type::<Movement>()
             .register_type::<MovementForce>()
             .register_type::<Jump>()
             .register_type::<JumpForce>()
+            .register_type::<JumpProfile>()
+            .register_type::<WallJump>()
+            .register_type::<LedgeGrab>()
+            .register_type::<Mantle>()
+            .register_type::<Crouch>()
+            .register_type::<FallDamage>()
+            .register_type::<SurfaceProperties>()
+            .register_type::<Option<MantleArc>>()
             .register_type::<Float>()
             .register_type::<FloatForce>()
+            .register_type::<GroundSnap>()
+            .register_type::<StepUp>()
+            .register_type::<StepUpForce>()
             .register_type::<Upright>()
             .register_type::<UprightForce>()
             .register_type::<ForceSettings>()
-            .register_type::<HashSet<Entity>>();
+            .register_type::<FrictionSettings>()
+            .register_type::<AirControl>()
+            .register_type::<ControllerEventState>()
+            .register_type::<Option<Entity>>()
+            .register_type::<HashSet<Entity>>()
+            .add_event::<SteppedUp>()
+            .add_event::<HeadBump>()
+            .add_event::<MantleStarted>()
+            .add_event::<MantleEnded>()
+            .add_event::<Jumped>()
+            .add_event::<Landed>()
+            .add_event::<LeftGround>()
+            .add_event::<GroundEntityChanged>()
+            .add_event::<StartedSlipping>()
+            .add_event::<CoyoteExpired>()
+            .add_event::<FallDamaged>();
 
         if self.tweaks {
             app.add_systems(Startup, setup_physics_context);
@@ -84,21 +145,42 @@ impl Plugin for WanderlustPlugin {
             app.add_systems(
                 self.schedule.clone(),
                 (
-                    crate::get_mass_from_rapier,
-                    crate::get_velocity_from_rapier,
-                    find_ground,
-                    determine_groundedness,
-                    gravity_force,
-                    movement_force,
-                    float_force,
-                    upright_force,
-                    jump_force,
-                    accumulate_forces,
-                    crate::apply_forces,
-                    crate::apply_ground_forces,
+                    (
+                        apply_gravity_fields::<B>,
+                        reorient_up_vector::<B>,
+                        get_mass_from_backend::<B>,
+                        get_velocity_from_backend::<B>,
+                        find_ground::<B>,
+                        add_wall_casters,
+                        find_walls::<B>,
+                        crouch::<B>,
+                        determine_groundedness,
+                    )
+                        .chain(),
+                    (
+                        gravity_force,
+                        wall_run::<B>,
+                        movement_force::<B>,
+                        float_force,
+                        step_up_force::<B>,
+                        upright_force,
+                        mantle::<B>,
+                        jump_force::<B>,
+                        head_bump::<B>,
+                        ledge_grab::<B>,
+                    )
+                        .chain(),
+                    (
+                        controller_events,
+                        fall_damage,
+                        accumulate_forces::<B>,
+                        apply_forces::<B>,
+                        apply_ground_forces::<B>,
+                    )
+                        .chain(),
                 )
                     .chain()
-                    .before(PhysicsSet::SyncBackend),
+                    .before(B::physics_set()),
             );
         }
 
@@ -1,6 +1,14 @@
-use crate::{controller::*, physics::*};
-use bevy::prelude::*;
-use bevy_rapier3d::prelude::*;
+use crate::{backend::*, controller::*, physics::*};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    na::Isometry3,
+    parry::{
+        bounding_volume::BoundingVolume,
+        query::{DefaultQueryDispatcher, PersistentQueryDispatcher},
+    },
+    prelude::*,
+    rapier::geometry::ContactManifold,
+};
 
 /// Contains common physics settings for character controllers.
 #[derive(Bundle)]
@@ -59,48 +67,264 @@ impl Default for RapierPhysicsBundle {
     }
 }
 
-/// Apply forces to the controller to make it float, move, jump, etc.
-pub fn apply_forces(
-    mut forces: Query<(&mut ExternalImpulse, &ControllerForce)>,
-    ctx: Res<RapierContext>,
-) {
-    let dt = ctx.integration_parameters.dt;
-    for (mut impulse, force) in &mut forces {
-        impulse.impulse += force.linear * dt;
-        impulse.torque_impulse += force.angular * dt;
+impl Default for CrouchShapes<Collider> {
+    /// The capsule from [`RapierPhysicsBundle`], and a shorter one for crouching.
+    fn default() -> Self {
+        Self {
+            standing: Collider::capsule(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.5, 0.0), 0.3),
+            crouching: Collider::capsule(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.1, 0.0), 0.3),
+        }
+    }
+}
+
+impl CastResult {
+    /// Use the first shape in the shape-cast as the cast result.
+    pub fn from_toi1(toi: Toi) -> Option<Self> {
+        toi.details.map(|details| Self {
+            toi: toi.toi,
+            normal: details.normal1,
+            point: details.witness1,
+        })
+    }
+
+    /// Use the second shape in the shape-cast as the cast result.
+    pub fn from_toi2(toi: Toi) -> Option<Self> {
+        toi.details.map(|details| Self {
+            toi: toi.toi,
+            normal: details.normal2,
+            point: details.witness2,
+        })
     }
 }
 
-/// Apply the opposing ground force to the entity we are pushing off of to float.
-pub fn apply_ground_forces(
-    mut impulses: Query<&mut ExternalImpulse>,
-    ground_forces: Query<(&GroundForce, &ViableGroundCast)>,
-    ctx: Res<RapierContext>,
-) {
-    let dt = ctx.integration_parameters.dt;
-    for (force, viable_ground) in &ground_forces {
-        if let Some(ground) = viable_ground.current() {
-            if let Ok(mut impulse) = impulses.get_mut(ground.entity) {
-                impulse.impulse += force.linear * dt;
-                impulse.torque_impulse += force.angular * dt;
-            }
+impl From<RayIntersection> for CastResult {
+    fn from(intersection: RayIntersection) -> Self {
+        Self {
+            toi: intersection.toi,
+            normal: intersection.normal,
+            point: intersection.point,
         }
     }
 }
 
-/// Sync rapier masses over to our masses.
-pub fn get_mass_from_rapier(mut query: Query<(&mut ControllerMass, &ReadMassProperties)>) {
-    for (mut mass, rapier_mass) in &mut query {
-        mass.mass = rapier_mass.mass;
-        mass.inertia = rapier_mass.principal_inertia;
-        mass.com = rapier_mass.local_center_of_mass;
+/// [`WanderlustBackend`] implementation for [Rapier](https://rapier.rs/).
+pub struct RapierBackend;
+
+/// Read-only access to the Rapier world used by [`RapierBackend`].
+#[derive(SystemParam)]
+pub struct RapierSpatialQuery<'w, 's> {
+    /// The Rapier physics context.
+    pub ctx: Res<'w, RapierContext>,
+    /// Global transforms of bodies.
+    pub globals: Query<'w, 's, &'static GlobalTransform>,
+    /// Colliders of bodies.
+    pub colliders: Query<'w, 's, &'static Collider>,
+    /// Mass properties of bodies.
+    pub masses: Query<'w, 's, &'static ReadMassProperties>,
+    /// Velocities of bodies.
+    pub velocities: Query<'w, 's, &'static Velocity>,
+    /// Friction of colliders.
+    pub frictions: Query<'w, 's, &'static Friction>,
+}
+
+impl RapierSpatialQuery<'_, '_> {
+    fn filter<'a>(&self, filter: CastFilter<'a>) -> QueryFilter<'a> {
+        let mut rapier_filter = QueryFilter::new();
+        if filter.exclude_sensors {
+            rapier_filter = rapier_filter.exclude_sensors();
+        }
+        rapier_filter.predicate = filter.predicate;
+        rapier_filter
     }
 }
 
-/// Sync rapier velocities over to our velocities.
-pub fn get_velocity_from_rapier(mut query: Query<(&mut ControllerVelocity, &Velocity)>) {
-    for (mut vel, rapier_vel) in &mut query {
-        vel.linear = rapier_vel.linvel;
-        vel.angular = rapier_vel.angvel;
+impl WanderlustBackend for RapierBackend {
+    type SpatialQuery = RapierSpatialQuery<'static, 'static>;
+    type Impulses = Query<'static, 'static, &'static mut ExternalImpulse>;
+    type Shape = Collider;
+    type PhysicsSet = PhysicsSet;
+
+    fn physics_set() -> PhysicsSet {
+        PhysicsSet::SyncBackend
+    }
+
+    fn dt(query: &RapierSpatialQuery) -> f32 {
+        query.ctx.integration_parameters.dt
+    }
+
+    fn collider_shape(query: &RapierSpatialQuery, entity: Entity) -> Option<Collider> {
+        query.colliders.get(entity).ok().cloned()
+    }
+
+    fn set_collider_shape(commands: &mut Commands, entity: Entity, shape: Collider) {
+        commands.entity(entity).insert(shape);
+    }
+
+    fn cast_shape(
+        query: &RapierSpatialQuery,
+        shape: &Collider,
+        position: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)> {
+        let (entity, toi) = query.ctx.cast_shape(
+            position,
+            rotation,
+            direction,
+            shape,
+            max_toi,
+            true,
+            query.filter(filter),
+        )?;
+
+        if toi.toi <= f32::EPSILON {
+            return None;
+        }
+
+        CastResult::from_toi1(toi).map(|cast| (entity, cast))
+    }
+
+    fn cast_ray(
+        query: &RapierSpatialQuery,
+        origin: Vec3,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)> {
+        query
+            .ctx
+            .cast_ray_and_get_normal(origin, direction, max_toi, true, query.filter(filter))
+            .map(|(entity, inter)| (entity, inter.into()))
+    }
+
+    fn shape_extent(shape: &Collider, direction: Vec3) -> f32 {
+        shape
+            .cast_local_ray(Vec3::ZERO, direction, 10.0, false)
+            .unwrap_or(0.)
+    }
+
+    fn contact_manifolds(
+        query: &RapierSpatialQuery,
+        shape: &Collider,
+        position: Vec3,
+        rotation: Quat,
+        filter: CastFilter,
+    ) -> Vec<(Entity, Vec3)> {
+        let ctx = &*query.ctx;
+        let physics_scale = ctx.physics_scale();
+        let filter = query.filter(filter);
+
+        let shape = &shape.raw;
+        let shape_iso = Isometry3 {
+            translation: (position * physics_scale).into(),
+            rotation: rotation.into(),
+        };
+
+        let shape_aabb = shape.compute_aabb(&shape_iso).loosened(FUDGE);
+
+        let mut manifolds = Vec::new();
+        ctx.query_pipeline
+            .colliders_with_aabb_intersecting_aabb(&shape_aabb, |handle| {
+                if let Some(collider) = ctx.colliders.get(*handle) {
+                    if RapierContext::with_query_filter(ctx, filter, |rapier_filter| {
+                        rapier_filter.test(&ctx.bodies, *handle, collider)
+                    }) {
+                        let mut new_manifolds: Vec<ContactManifold> = Vec::new();
+                        let pos12 = shape_iso.inv_mul(collider.position());
+                        let _ = DefaultQueryDispatcher.contact_manifolds(
+                            &pos12,
+                            shape.as_ref(),
+                            collider.shape(),
+                            0.01,
+                            &mut new_manifolds,
+                            &mut None,
+                        );
+
+                        if let Some(entity) = ctx.collider_entity(*handle) {
+                            let rotation = query
+                                .globals
+                                .get(entity)
+                                .map(|global| global.to_scale_rotation_translation().1)
+                                .unwrap_or(Quat::IDENTITY);
+                            manifolds.extend(new_manifolds.into_iter().map(|manifold| {
+                                let local_normal: Vec3 = manifold.local_n2.into();
+                                (entity, rotation * local_normal)
+                            }));
+                        }
+                    }
+                }
+
+                true
+            });
+
+        manifolds
+    }
+
+    fn point_intersections(
+        query: &RapierSpatialQuery,
+        point: Vec3,
+        filter: CastFilter,
+    ) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        query
+            .ctx
+            .intersections_with_point(point, query.filter(filter), |entity| {
+                entities.push(entity);
+                true
+            });
+        entities
+    }
+
+    fn in_contact(query: &RapierSpatialQuery, entity: Entity) -> bool {
+        query.ctx.contacts_with(entity).next().is_some()
+    }
+
+    fn collider_body(query: &RapierSpatialQuery, collider: Entity) -> Entity {
+        query.ctx.collider_parent(collider).unwrap_or(collider)
+    }
+
+    fn velocity(query: &RapierSpatialQuery, entity: Entity) -> ControllerVelocity {
+        let velocity = query.velocities.get(entity).copied().unwrap_or_default();
+        ControllerVelocity {
+            linear: velocity.linvel,
+            angular: velocity.angvel,
+        }
+    }
+
+    fn mass(query: &RapierSpatialQuery, entity: Entity) -> ControllerMass {
+        let mass = if let Ok(mass) = query.masses.get(entity) {
+            (**mass).clone()
+        } else {
+            MassProperties::default()
+        };
+
+        ControllerMass {
+            mass: mass.mass,
+            inertia: mass.principal_inertia,
+            com: mass.local_center_of_mass,
+        }
+    }
+
+    fn friction(query: &RapierSpatialQuery, entity: Entity) -> f32 {
+        query
+            .frictions
+            .get(entity)
+            .copied()
+            .unwrap_or_default()
+            .coefficient
+    }
+
+    fn apply_impulse(
+        impulses: &mut Query<&mut ExternalImpulse>,
+        entity: Entity,
+        linear: Vec3,
+        angular: Vec3,
+    ) {
+        if let Ok(mut impulse) = impulses.get_mut(entity) {
+            impulse.impulse += linear;
+            impulse.torque_impulse += angular;
+        }
     }
 }
@@ -0,0 +1,334 @@
+use crate::{backend::*, controller::*, physics::*};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier2d::{
+    na::{Isometry2, UnitComplex},
+    parry::{
+        bounding_volume::BoundingVolume,
+        query::{DefaultQueryDispatcher, PersistentQueryDispatcher},
+    },
+    prelude::*,
+    rapier::geometry::ContactManifold,
+};
+
+/// Contains common physics settings for 2D character controllers.
+#[derive(Bundle)]
+pub struct Rapier2dPhysicsBundle {
+    /// See [`RigidBody`].
+    pub rigidbody: RigidBody,
+    /// See [`Collider`].
+    pub collider: Collider,
+    /// See [`Velocity`].
+    pub velocity: Velocity,
+    /// See [`GravityScale`].
+    pub gravity: GravityScale,
+    /// See [`Sleeping`].
+    pub sleeping: Sleeping,
+    /// See [`Ccd`].
+    pub ccd: Ccd,
+    /// See [`ExternalImpulse`].
+    pub force: ExternalImpulse,
+    /// See [`LockedAxes`].
+    pub locked_axes: LockedAxes,
+    /// See [`Friction`].
+    pub friction: Friction,
+    /// See [`Damping`].
+    pub damping: Damping,
+    /// See [`Restitution`].
+    pub restitution: Restitution,
+    /// See [`ReadMassProperties`].
+    pub read_mass_properties: ReadMassProperties,
+}
+
+impl Default for Rapier2dPhysicsBundle {
+    fn default() -> Self {
+        Self {
+            rigidbody: default(),
+            collider: Collider::capsule(Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.5), 0.3),
+            velocity: default(),
+            gravity: GravityScale(0.0),
+            sleeping: default(),
+            ccd: default(),
+            force: default(),
+            locked_axes: default(),
+            friction: Friction {
+                coefficient: 0.0,
+                combine_rule: CoefficientCombineRule::Min,
+            },
+            damping: Damping {
+                linear_damping: 0.0,
+                angular_damping: 0.0,
+            },
+            restitution: Restitution {
+                coefficient: 0.0,
+                combine_rule: CoefficientCombineRule::Min,
+            },
+            read_mass_properties: default(),
+        }
+    }
+}
+
+impl Default for CrouchShapes<Collider> {
+    /// The capsule from [`Rapier2dPhysicsBundle`], and a shorter one for crouching.
+    fn default() -> Self {
+        Self {
+            standing: Collider::capsule(Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.5), 0.3),
+            crouching: Collider::capsule(Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.1), 0.3),
+        }
+    }
+}
+
+/// [`WanderlustBackend`] implementation for 2D [Rapier](https://rapier.rs/).
+///
+/// The controller works in the XY plane, so all of the usual components
+/// ([`GroundCaster`], [`Float`], [`Movement`], [`Jump`], [`Upright`], ...) can be used
+/// for 2D controllers as well. Vectors are projected onto the XY plane and rotations
+/// are taken around the Z axis.
+pub struct Rapier2dBackend;
+
+/// Read-only access to the 2D Rapier world used by [`Rapier2dBackend`].
+#[derive(SystemParam)]
+pub struct Rapier2dSpatialQuery<'w, 's> {
+    /// The Rapier physics context.
+    pub ctx: Res<'w, RapierContext>,
+    /// Global transforms of bodies.
+    pub globals: Query<'w, 's, &'static GlobalTransform>,
+    /// Colliders of bodies.
+    pub colliders: Query<'w, 's, &'static Collider>,
+    /// Mass properties of bodies.
+    pub masses: Query<'w, 's, &'static ReadMassProperties>,
+    /// Velocities of bodies.
+    pub velocities: Query<'w, 's, &'static Velocity>,
+    /// Friction of colliders.
+    pub frictions: Query<'w, 's, &'static Friction>,
+}
+
+impl Rapier2dSpatialQuery<'_, '_> {
+    fn filter<'a>(&self, filter: CastFilter<'a>) -> QueryFilter<'a> {
+        let mut rapier_filter = QueryFilter::new();
+        if filter.exclude_sensors {
+            rapier_filter = rapier_filter.exclude_sensors();
+        }
+        rapier_filter.predicate = filter.predicate;
+        rapier_filter
+    }
+}
+
+/// Angle of a rotation around the Z axis.
+fn angle(rotation: Quat) -> f32 {
+    rotation.to_euler(EulerRot::ZYX).0
+}
+
+impl WanderlustBackend for Rapier2dBackend {
+    type SpatialQuery = Rapier2dSpatialQuery<'static, 'static>;
+    type Impulses = Query<'static, 'static, &'static mut ExternalImpulse>;
+    type Shape = Collider;
+    type PhysicsSet = PhysicsSet;
+
+    fn physics_set() -> PhysicsSet {
+        PhysicsSet::SyncBackend
+    }
+
+    fn dt(query: &Rapier2dSpatialQuery) -> f32 {
+        query.ctx.integration_parameters.dt
+    }
+
+    fn collider_shape(query: &Rapier2dSpatialQuery, entity: Entity) -> Option<Collider> {
+        query.colliders.get(entity).ok().cloned()
+    }
+
+    fn set_collider_shape(commands: &mut Commands, entity: Entity, shape: Collider) {
+        commands.entity(entity).insert(shape);
+    }
+
+    fn cast_shape(
+        query: &Rapier2dSpatialQuery,
+        shape: &Collider,
+        position: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)> {
+        let (entity, toi) = query.ctx.cast_shape(
+            position.truncate(),
+            angle(rotation),
+            direction.truncate(),
+            shape,
+            max_toi,
+            true,
+            query.filter(filter),
+        )?;
+
+        if toi.toi <= f32::EPSILON {
+            return None;
+        }
+
+        toi.details.map(|details| {
+            (
+                entity,
+                CastResult {
+                    toi: toi.toi,
+                    normal: details.normal1.extend(0.0),
+                    point: details.witness1.extend(0.0),
+                },
+            )
+        })
+    }
+
+    fn cast_ray(
+        query: &Rapier2dSpatialQuery,
+        origin: Vec3,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)> {
+        query
+            .ctx
+            .cast_ray_and_get_normal(
+                origin.truncate(),
+                direction.truncate(),
+                max_toi,
+                true,
+                query.filter(filter),
+            )
+            .map(|(entity, inter)| {
+                (
+                    entity,
+                    CastResult {
+                        toi: inter.toi,
+                        normal: inter.normal.extend(0.0),
+                        point: inter.point.extend(0.0),
+                    },
+                )
+            })
+    }
+
+    fn shape_extent(shape: &Collider, direction: Vec3) -> f32 {
+        shape
+            .cast_local_ray(Vec2::ZERO, direction.truncate(), 10.0, false)
+            .unwrap_or(0.)
+    }
+
+    fn contact_manifolds(
+        query: &Rapier2dSpatialQuery,
+        shape: &Collider,
+        position: Vec3,
+        rotation: Quat,
+        filter: CastFilter,
+    ) -> Vec<(Entity, Vec3)> {
+        let ctx = &*query.ctx;
+        let physics_scale = ctx.physics_scale();
+        let filter = query.filter(filter);
+
+        let shape = &shape.raw;
+        let shape_iso = Isometry2 {
+            translation: (position.truncate() * physics_scale).into(),
+            rotation: UnitComplex::new(angle(rotation)),
+        };
+
+        let shape_aabb = shape.compute_aabb(&shape_iso).loosened(FUDGE);
+
+        let mut manifolds = Vec::new();
+        ctx.query_pipeline
+            .colliders_with_aabb_intersecting_aabb(&shape_aabb, |handle| {
+                if let Some(collider) = ctx.colliders.get(*handle) {
+                    if RapierContext::with_query_filter(ctx, filter, |rapier_filter| {
+                        rapier_filter.test(&ctx.bodies, *handle, collider)
+                    }) {
+                        let mut new_manifolds: Vec<ContactManifold> = Vec::new();
+                        let pos12 = shape_iso.inv_mul(collider.position());
+                        let _ = DefaultQueryDispatcher.contact_manifolds(
+                            &pos12,
+                            shape.as_ref(),
+                            collider.shape(),
+                            0.01,
+                            &mut new_manifolds,
+                            &mut None,
+                        );
+
+                        if let Some(entity) = ctx.collider_entity(*handle) {
+                            let rotation = query
+                                .globals
+                                .get(entity)
+                                .map(|global| global.to_scale_rotation_translation().1)
+                                .unwrap_or(Quat::IDENTITY);
+                            manifolds.extend(new_manifolds.into_iter().map(|manifold| {
+                                let local_normal: Vec2 = manifold.local_n2.into();
+                                (entity, rotation * local_normal.extend(0.0))
+                            }));
+                        }
+                    }
+                }
+
+                true
+            });
+
+        manifolds
+    }
+
+    fn point_intersections(
+        query: &Rapier2dSpatialQuery,
+        point: Vec3,
+        filter: CastFilter,
+    ) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        query
+            .ctx
+            .intersections_with_point(point.truncate(), query.filter(filter), |entity| {
+                entities.push(entity);
+                true
+            });
+        entities
+    }
+
+    fn in_contact(query: &Rapier2dSpatialQuery, entity: Entity) -> bool {
+        query.ctx.contacts_with(entity).next().is_some()
+    }
+
+    fn collider_body(query: &Rapier2dSpatialQuery, collider: Entity) -> Entity {
+        query.ctx.collider_parent(collider).unwrap_or(collider)
+    }
+
+    fn velocity(query: &Rapier2dSpatialQuery, entity: Entity) -> ControllerVelocity {
+        let velocity = query.velocities.get(entity).copied().unwrap_or_default();
+        ControllerVelocity {
+            linear: velocity.linvel.extend(0.0),
+            angular: Vec3::Z * velocity.angvel,
+        }
+    }
+
+    fn mass(query: &Rapier2dSpatialQuery, entity: Entity) -> ControllerMass {
+        let mass = if let Ok(mass) = query.masses.get(entity) {
+            (**mass).clone()
+        } else {
+            MassProperties::default()
+        };
+
+        ControllerMass {
+            mass: mass.mass,
+            inertia: Vec3::Z * mass.principal_inertia,
+            com: mass.local_center_of_mass.extend(0.0),
+        }
+    }
+
+    fn friction(query: &Rapier2dSpatialQuery, entity: Entity) -> f32 {
+        query
+            .frictions
+            .get(entity)
+            .copied()
+            .unwrap_or_default()
+            .coefficient
+    }
+
+    fn apply_impulse(
+        impulses: &mut Query<&mut ExternalImpulse>,
+        entity: Entity,
+        linear: Vec3,
+        angular: Vec3,
+    ) {
+        if let Ok(mut impulse) = impulses.get_mut(entity) {
+            impulse.impulse += linear.truncate();
+            impulse.torque_impulse += angular.z;
+        }
+    }
+}
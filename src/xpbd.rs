@@ -0,0 +1,341 @@
+use crate::{
+    backend::*,
+    controller::{CastResult, CrouchShapes},
+    physics::*,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_xpbd_3d::{
+    parry::{
+        self,
+        math::Isometry,
+        query::{Ray, RayCast},
+    },
+    prelude::*,
+};
+
+/// Contains common physics settings for character controllers.
+#[derive(Bundle)]
+pub struct XpbdPhysicsBundle {
+    /// See [`RigidBody`].
+    pub rigidbody: RigidBody,
+    /// See [`Collider`].
+    pub collider: Collider,
+    /// See [`LinearVelocity`].
+    pub linear_velocity: LinearVelocity,
+    /// See [`AngularVelocity`].
+    pub angular_velocity: AngularVelocity,
+    /// See [`GravityScale`].
+    pub gravity: GravityScale,
+    /// See [`ExternalImpulse`].
+    pub impulse: ExternalImpulse,
+    /// See [`ExternalAngularImpulse`].
+    pub angular_impulse: ExternalAngularImpulse,
+    /// See [`LockedAxes`].
+    pub locked_axes: LockedAxes,
+    /// See [`Friction`].
+    pub friction: Friction,
+    /// See [`LinearDamping`].
+    pub linear_damping: LinearDamping,
+    /// See [`AngularDamping`].
+    pub angular_damping: AngularDamping,
+    /// See [`Restitution`].
+    pub restitution: Restitution,
+    /// See [`CollidingEntities`].
+    pub colliding_entities: CollidingEntities,
+}
+
+impl Default for XpbdPhysicsBundle {
+    fn default() -> Self {
+        Self {
+            rigidbody: RigidBody::Dynamic,
+            collider: Collider::capsule_endpoints(
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.5, 0.0),
+                0.3,
+            ),
+            linear_velocity: default(),
+            angular_velocity: default(),
+            gravity: GravityScale(0.0),
+            impulse: default(),
+            angular_impulse: default(),
+            locked_axes: default(),
+            friction: Friction {
+                dynamic_coefficient: 0.0,
+                static_coefficient: 0.0,
+                combine_rule: CoefficientCombine::Min,
+            },
+            linear_damping: LinearDamping(0.0),
+            angular_damping: AngularDamping(0.0),
+            restitution: Restitution {
+                coefficient: 0.0,
+                combine_rule: CoefficientCombine::Min,
+            },
+            colliding_entities: default(),
+        }
+    }
+}
+
+impl Default for CrouchShapes<Collider> {
+    /// The capsule from [`XpbdPhysicsBundle`], and a shorter one for crouching.
+    fn default() -> Self {
+        Self {
+            standing: Collider::capsule_endpoints(
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.5, 0.0),
+                0.3,
+            ),
+            crouching: Collider::capsule_endpoints(
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.1, 0.0),
+                0.3,
+            ),
+        }
+    }
+}
+
+/// [`WanderlustBackend`] implementation for [bevy_xpbd](https://github.com/Jondolf/bevy_xpbd).
+pub struct XpbdBackend;
+
+/// Access to the xpbd world used by [`XpbdBackend`].
+#[derive(SystemParam)]
+pub struct XpbdSpatialQuery<'w, 's> {
+    /// The xpbd spatial query pipeline.
+    pub spatial: SpatialQuery<'w, 's>,
+    /// Timestep of the physics simulation.
+    pub dt: Res<'w, DeltaTime>,
+    /// Global transforms of bodies.
+    pub globals: Query<'w, 's, &'static GlobalTransform>,
+    /// Colliders of bodies.
+    pub colliders: Query<'w, 's, &'static Collider>,
+    /// Sensor colliders.
+    pub sensors: Query<'w, 's, (), With<Sensor>>,
+    /// Mass properties of bodies.
+    pub masses: Query<'w, 's, (&'static Mass, &'static Inertia, &'static CenterOfMass)>,
+    /// Velocities of bodies.
+    pub velocities: Query<'w, 's, (&'static LinearVelocity, &'static AngularVelocity)>,
+    /// Friction of colliders.
+    pub frictions: Query<'w, 's, &'static Friction>,
+    /// Entities the controller is touching.
+    pub colliding: Query<'w, 's, &'static CollidingEntities>,
+}
+
+impl XpbdSpatialQuery<'_, '_> {
+    fn test(&self, entity: Entity, filter: &CastFilter) -> bool {
+        filter.test(entity, self.sensors.contains(entity))
+    }
+}
+
+/// Maximum number of hits to consider in a cast before giving up on
+/// finding one that passes the [`CastFilter`].
+const MAX_HITS: u32 = 16;
+
+impl WanderlustBackend for XpbdBackend {
+    type SpatialQuery = XpbdSpatialQuery<'static, 'static>;
+    type Impulses = Query<
+        'static,
+        'static,
+        (
+            &'static mut ExternalImpulse,
+            &'static mut ExternalAngularImpulse,
+        ),
+    >;
+    type Shape = Collider;
+    type PhysicsSet = PhysicsSet;
+
+    fn physics_set() -> PhysicsSet {
+        PhysicsSet::Prepare
+    }
+
+    fn dt(query: &XpbdSpatialQuery) -> f32 {
+        query.dt.0
+    }
+
+    fn collider_shape(query: &XpbdSpatialQuery, entity: Entity) -> Option<Collider> {
+        query.colliders.get(entity).ok().cloned()
+    }
+
+    fn set_collider_shape(commands: &mut Commands, entity: Entity, shape: Collider) {
+        commands.entity(entity).insert(shape);
+    }
+
+    fn cast_shape(
+        query: &XpbdSpatialQuery,
+        shape: &Collider,
+        position: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)> {
+        let mut hits = query.spatial.shape_hits(
+            shape,
+            position,
+            rotation,
+            direction,
+            max_toi,
+            MAX_HITS,
+            true,
+            SpatialQueryFilter::default(),
+        );
+        hits.retain(|hit| query.test(hit.entity, &filter));
+        hits.sort_by(|a, b| a.time_of_impact.total_cmp(&b.time_of_impact));
+        let hit = hits.first()?;
+
+        if hit.time_of_impact <= f32::EPSILON {
+            return None;
+        }
+
+        // Hit data is in the local space of the cast shape, so move it to where
+        // the shape was at the time of impact.
+        let shape_position = position + direction * hit.time_of_impact;
+        Some((
+            hit.entity,
+            CastResult {
+                toi: hit.time_of_impact,
+                normal: -(rotation * hit.normal1),
+                point: shape_position + rotation * hit.point1,
+            },
+        ))
+    }
+
+    fn cast_ray(
+        query: &XpbdSpatialQuery,
+        origin: Vec3,
+        direction: Vec3,
+        max_toi: f32,
+        filter: CastFilter,
+    ) -> Option<(Entity, CastResult)> {
+        let mut hits = query.spatial.ray_hits(
+            origin,
+            direction,
+            max_toi,
+            MAX_HITS,
+            true,
+            SpatialQueryFilter::default(),
+        );
+        hits.retain(|hit| query.test(hit.entity, &filter));
+        hits.sort_by(|a, b| a.time_of_impact.total_cmp(&b.time_of_impact));
+        let hit = hits.first()?;
+
+        Some((
+            hit.entity,
+            CastResult {
+                toi: hit.time_of_impact,
+                normal: hit.normal,
+                point: origin + direction * hit.time_of_impact,
+            },
+        ))
+    }
+
+    fn shape_extent(shape: &Collider, direction: Vec3) -> f32 {
+        let ray = Ray::new(Vec3::ZERO.into(), direction.into());
+        shape
+            .get_shape()
+            .cast_local_ray(&ray, 10.0, false)
+            .unwrap_or(0.)
+    }
+
+    fn contact_manifolds(
+        query: &XpbdSpatialQuery,
+        shape: &Collider,
+        position: Vec3,
+        rotation: Quat,
+        filter: CastFilter,
+    ) -> Vec<(Entity, Vec3)> {
+        let shape_iso: Isometry<f32> = (position, rotation).into();
+
+        query
+            .spatial
+            .shape_intersections(shape, position, rotation, SpatialQueryFilter::default())
+            .into_iter()
+            .filter(|entity| query.test(*entity, &filter))
+            .filter_map(|entity| {
+                let collider = query.colliders.get(entity).ok()?;
+                let (_, collider_rotation, collider_position) = query
+                    .globals
+                    .get(entity)
+                    .ok()?
+                    .to_scale_rotation_translation();
+                let collider_iso: Isometry<f32> = (collider_position, collider_rotation).into();
+
+                let contact = parry::query::contact(
+                    &shape_iso,
+                    shape.get_shape().as_ref(),
+                    &collider_iso,
+                    collider.get_shape().as_ref(),
+                    0.01,
+                )
+                .ok()??;
+
+                Some((entity, Vec3::from(contact.normal2.into_inner())))
+            })
+            .collect()
+    }
+
+    fn point_intersections(
+        query: &XpbdSpatialQuery,
+        point: Vec3,
+        filter: CastFilter,
+    ) -> Vec<Entity> {
+        let mut entities = query
+            .spatial
+            .point_intersections(point, SpatialQueryFilter::default());
+        entities.retain(|entity| query.test(*entity, &filter));
+        entities
+    }
+
+    fn in_contact(query: &XpbdSpatialQuery, entity: Entity) -> bool {
+        query
+            .colliding
+            .get(entity)
+            .map_or(false, |colliding| !colliding.is_empty())
+    }
+
+    fn collider_body(_: &XpbdSpatialQuery, collider: Entity) -> Entity {
+        // Colliders are always attached directly to their body in xpbd.
+        collider
+    }
+
+    fn velocity(query: &XpbdSpatialQuery, entity: Entity) -> ControllerVelocity {
+        let (linear, angular) = query
+            .velocities
+            .get(entity)
+            .map_or((Vec3::ZERO, Vec3::ZERO), |(linear, angular)| {
+                (linear.0, angular.0)
+            });
+        ControllerVelocity { linear, angular }
+    }
+
+    fn mass(query: &XpbdSpatialQuery, entity: Entity) -> ControllerMass {
+        let Ok((mass, inertia, com)) = query.masses.get(entity) else {
+            return ControllerMass::default();
+        };
+
+        ControllerMass {
+            mass: mass.0,
+            inertia: Vec3::new(inertia.0.x_axis.x, inertia.0.y_axis.y, inertia.0.z_axis.z),
+            com: com.0,
+        }
+    }
+
+    fn friction(query: &XpbdSpatialQuery, entity: Entity) -> f32 {
+        query
+            .frictions
+            .get(entity)
+            .copied()
+            .unwrap_or_default()
+            .dynamic_coefficient
+    }
+
+    fn apply_impulse(
+        impulses: &mut Query<(&mut ExternalImpulse, &mut ExternalAngularImpulse)>,
+        entity: Entity,
+        linear: Vec3,
+        angular: Vec3,
+    ) {
+        if let Ok((mut impulse, mut angular_impulse)) = impulses.get_mut(entity) {
+            impulse.apply_impulse(linear);
+            angular_impulse.apply_impulse(angular);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Controller behavior in the headless analytic world.
+//!
+//! Run with `cargo test --test analytic --features analytic`.
+
+#![cfg(feature = "analytic")]
+
+use bevy::{
+    asset::AssetPlugin, gizmos::GizmoPlugin, prelude::*, render::render_resource::Shader,
+    time::TimeUpdateStrategy,
+};
+use bevy_mod_wanderlust::*;
+use std::time::Duration;
+
+const DT: f32 = 1.0 / 60.0;
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .add_asset::<Shader>()
+        .add_plugins((
+            GizmoPlugin,
+            AnalyticPhysicsPlugin::default(),
+            WanderlustPlugin::<AnalyticBackend>::new(),
+        ))
+        .insert_resource(AnalyticConfig { dt: DT })
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            DT,
+        )));
+    app
+}
+
+fn spawn_static(app: &mut App, shape: AnalyticShape, transform: Transform) -> Entity {
+    app.world
+        .spawn((
+            AnalyticCollider { shape, ..default() },
+            TransformBundle {
+                local: transform,
+                global: transform.into(),
+            },
+        ))
+        .id()
+}
+
+fn spawn_controller(app: &mut App, translation: Vec3) -> Entity {
+    let transform = Transform::from_translation(translation);
+    app.world
+        .spawn((
+            Controller::default(),
+            ControllerInput::default(),
+            ControllerPhysicsBundle::default(),
+            AnalyticPhysicsBundle::default(),
+            TransformBundle {
+                local: transform,
+                global: transform.into(),
+            },
+        ))
+        .id()
+}
+
+fn flat_ground(app: &mut App) -> Entity {
+    spawn_static(app, AnalyticShape::Plane { normal: Vec3::Y }, default())
+}
+
+fn slope(app: &mut App, degrees: f32) -> Entity {
+    spawn_static(
+        app,
+        AnalyticShape::Plane { normal: Vec3::Y },
+        Transform::from_rotation(Quat::from_rotation_z(degrees.to_radians())),
+    )
+}
+
+fn run(app: &mut App, ticks: usize) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+fn translation(app: &App, entity: Entity) -> Vec3 {
+    app.world.get::<Transform>(entity).unwrap().translation
+}
+
+#[test]
+fn floats_at_float_distance() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.5, 0.0));
+
+    run(&mut app, 300);
+
+    // The float spring only pushes upwards, so it settles where it cancels gravity.
+    let float = app.world.get::<Float>(controller).unwrap();
+    let gravity = app.world.get::<Gravity>(controller).unwrap();
+    let sag = gravity.acceleration.abs() / float.spring.strength.get(Vec3::ONE).y;
+    let height = translation(&app, controller).y;
+    assert!(
+        (height - (float.distance - sag)).abs() < 0.02,
+        "rest height {height} should be close to float distance {}",
+        float.distance
+    );
+    assert!(**app.world.get::<Grounded>(controller).unwrap());
+}
+
+#[test]
+fn jump_reaches_apex_and_lands() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 0.55, 0.0));
+    run(&mut app, 120);
+    let rest = translation(&app, controller).y;
+
+    app.world
+        .get_mut::<ControllerInput>(controller)
+        .unwrap()
+        .jumping = true;
+    let mut apex = rest;
+    let mut left_ground = false;
+    for _ in 0..240 {
+        app.update();
+        apex = apex.max(translation(&app, controller).y);
+        left_ground |= !**app.world.get::<Grounded>(controller).unwrap();
+    }
+
+    assert!(left_ground, "controller never left the ground");
+    assert!(apex > rest + 0.2, "apex {apex} barely above rest {rest}");
+    assert!((translation(&app, controller).y - rest).abs() < 0.05);
+    assert!(**app.world.get::<Grounded>(controller).unwrap());
+}
+
+#[test]
+fn slips_on_steep_slope() {
+    let mut app = app();
+    slope(&mut app, 50.0);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+
+    run(&mut app, 60);
+    let start = translation(&app, controller);
+    run(&mut app, 60);
+
+    // The slope normal leans towards -X, so downhill is -X.
+    let ground = app.world.get::<GroundCast>(controller).unwrap();
+    assert!(!ground.current().unwrap().stable);
+    assert!(translation(&app, controller).x < start.x);
+}
+
+#[test]
+fn stands_still_on_gentle_slope() {
+    let mut app = app();
+    slope(&mut app, 20.0);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 0.6, 0.0));
+
+    run(&mut app, 120);
+    let start = translation(&app, controller);
+    run(&mut app, 120);
+
+    assert!(translation(&app, controller).distance(start) < 0.05);
+    assert!(**app.world.get::<Grounded>(controller).unwrap());
+}
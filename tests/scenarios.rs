@@ -0,0 +1,1626 @@
+//! Canonical controller scenarios simulated headlessly with Rapier.
+//!
+//! Each scenario scripts input for a fixed number of ticks and checks measurable
+//! outcomes, so regressions in movement, friction and groundedness show up as
+//! failing tests instead of "it feels different".
+
+#![cfg(feature = "rapier")]
+
+use bevy::{
+    asset::AssetPlugin, ecs::event::ManualEventReader, gizmos::GizmoPlugin, prelude::*,
+    render::render_resource::Shader, scene::ScenePlugin, time::TimeUpdateStrategy,
+};
+use bevy_mod_wanderlust::*;
+use bevy_rapier3d::prelude::*;
+use std::time::Duration;
+
+const DT: f32 = 1.0 / 60.0;
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+        ScenePlugin,
+    ))
+    .add_asset::<Shader>()
+    .add_asset::<Mesh>()
+    .add_plugins((
+        GizmoPlugin,
+        RapierPhysicsPlugin::<NoUserData>::default(),
+        WanderlustPlugin::<RapierBackend>::default(),
+    ))
+    .insert_resource(RapierConfiguration {
+        timestep_mode: TimestepMode::Fixed {
+            dt: DT,
+            substeps: 1,
+        },
+        ..default()
+    })
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        DT,
+    )));
+    app
+}
+
+fn spawn_controller(app: &mut App, translation: Vec3) -> Entity {
+    app.world
+        .spawn((
+            Controller::default(),
+            ControllerInput::default(),
+            ControllerPhysicsBundle::default(),
+            RapierPhysicsBundle {
+                locked_axes: LockedAxes::ROTATION_LOCKED,
+                ..default()
+            },
+            TransformBundle::from_transform(Transform::from_translation(translation)),
+        ))
+        .id()
+}
+
+fn spawn_box(app: &mut App, transform: Transform) -> Entity {
+    app.world
+        .spawn((
+            Collider::cuboid(0.5, 0.5, 0.5),
+            TransformBundle::from_transform(transform),
+        ))
+        .id()
+}
+
+fn flat_ground(app: &mut App) -> Entity {
+    spawn_box(
+        app,
+        Transform {
+            translation: Vec3::new(0.0, -0.5, 0.0),
+            scale: Vec3::new(50.0, 1.0, 50.0),
+            ..default()
+        },
+    )
+}
+
+/// Same layout as the `steps` in the playground example, climbing towards +X.
+fn steps(app: &mut App, step_increment: f32, width: f32, steps: u32) {
+    for step in 1..=steps {
+        spawn_box(
+            app,
+            Transform {
+                translation: Vec3::new(
+                    step as f32 * width,
+                    step as f32 * step_increment / 2.0,
+                    0.0,
+                ),
+                scale: Vec3::new(width, step as f32 * step_increment, 5.0),
+                ..default()
+            },
+        );
+    }
+}
+
+fn run(app: &mut App, ticks: usize) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+fn translation(app: &App, entity: Entity) -> Vec3 {
+    app.world.get::<Transform>(entity).unwrap().translation
+}
+
+fn grounded(app: &App, entity: Entity) -> bool {
+    **app.world.get::<Grounded>(entity).unwrap()
+}
+
+fn velocity(app: &App, entity: Entity) -> Vec3 {
+    app.world.get::<ControllerVelocity>(entity).unwrap().linear
+}
+
+fn set_input(app: &mut App, entity: Entity, movement: Vec3, jumping: bool) {
+    let mut input = app.world.get_mut::<ControllerInput>(entity).unwrap();
+    input.movement = movement;
+    input.jumping = jumping;
+}
+
+/// Height the controller floats at once the float spring cancels out gravity.
+///
+/// The spring only pushes upwards, so it settles slightly below [`Float::distance`].
+fn rest_height(app: &App, entity: Entity) -> f32 {
+    let float = app.world.get::<Float>(entity).unwrap();
+    let gravity = app.world.get::<Gravity>(entity).unwrap();
+    float.distance - gravity.acceleration.abs() / float.spring.strength.get(Vec3::ONE).y
+}
+
+#[test]
+fn stand_still_on_flat_ground() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+
+    run(&mut app, 180);
+    assert!(grounded(&app, controller));
+    let start = translation(&app, controller);
+    let expected = rest_height(&app, controller);
+    assert!(
+        (start.y - expected).abs() < 0.02,
+        "rest height {} should be {expected}",
+        start.y
+    );
+
+    run(&mut app, 180);
+    let end = translation(&app, controller);
+    assert!(grounded(&app, controller));
+    assert!((end.y - start.y).abs() < 0.005, "bobbing at rest");
+    assert!(
+        (end - start).reject_from(Vec3::Y).length() < 0.005,
+        "lateral drift of {:?} while standing still",
+        end - start
+    );
+}
+
+#[test]
+fn walk_up_steps() {
+    let mut app = app();
+    flat_ground(&mut app);
+    steps(&mut app, 0.2, 0.3, 12);
+    // Landing at the top of the steps.
+    spawn_box(
+        &mut app,
+        Transform {
+            translation: Vec3::new(6.25, 1.2, 0.0),
+            scale: Vec3::new(5.0, 2.4, 5.0),
+            ..default()
+        },
+    );
+    let controller = spawn_controller(&mut app, Vec3::new(-1.0, 1.0, 0.0));
+    run(&mut app, 60);
+
+    set_input(&mut app, controller, Vec3::X, false);
+    let mut airborne_ticks = 0;
+    for _ in 0..120 {
+        app.update();
+        if !grounded(&app, controller) {
+            airborne_ticks += 1;
+        }
+    }
+    set_input(&mut app, controller, Vec3::ZERO, false);
+    run(&mut app, 60);
+
+    let top = 2.4 + rest_height(&app, controller);
+    let end = translation(&app, controller);
+    assert!(
+        end.x > 3.75,
+        "never reached the landing, stopped at {end:?}"
+    );
+    assert!(
+        (end.y - top).abs() < 0.02,
+        "standing at {} instead of on top of the steps at {top}",
+        end.y
+    );
+    assert!(end.z.abs() < 0.05, "drifted sideways to z = {}", end.z);
+    // Step edges can hide the ground from the cast for a tick or two.
+    assert!(
+        airborne_ticks <= 3,
+        "lost the ground for {airborne_ticks} ticks on the steps"
+    );
+}
+
+#[test]
+fn slide_on_steep_slope() {
+    let mut app = app();
+    let slope = 50f32.to_radians();
+    spawn_box(
+        &mut app,
+        Transform {
+            rotation: Quat::from_rotation_z(slope),
+            scale: Vec3::new(12.0, 1.0, 4.0),
+            ..default()
+        },
+    );
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.5, 0.0));
+
+    run(&mut app, 60);
+    let ground = app.world.get::<GroundCast>(controller).unwrap();
+    assert!(ground.current().is_some_and(|ground| !ground.stable));
+    let start = translation(&app, controller);
+
+    run(&mut app, 30);
+    let end = translation(&app, controller);
+
+    // Rotating around Z tilts the top face towards -X, so downhill is -X.
+    assert!(
+        end.x < start.x - 0.01,
+        "did not slide: {start:?} -> {end:?}"
+    );
+    assert!(end.y < start.y, "slid uphill: {start:?} -> {end:?}");
+    assert!((end.z - start.z).abs() < 0.01, "slid off to the side");
+}
+
+#[test]
+fn jump_and_land() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    run(&mut app, 120);
+    let rest = translation(&app, controller);
+
+    set_input(&mut app, controller, Vec3::ZERO, true);
+    let mut apex = rest.y;
+    let mut left_at = None;
+    let mut landed_at = None;
+    for tick in 0..180 {
+        app.update();
+        apex = apex.max(translation(&app, controller).y);
+        match (grounded(&app, controller), left_at, landed_at) {
+            (false, None, _) => left_at = Some(tick),
+            (true, Some(_), None) => landed_at = Some(tick),
+            _ => {}
+        }
+    }
+
+    let left_at = left_at.expect("never left the ground");
+    let landed_at = landed_at.expect("never landed");
+    assert!(left_at < 20, "left the ground late, at tick {left_at}");
+    assert!(
+        landed_at - left_at > 15,
+        "only airborne for {} ticks",
+        landed_at - left_at
+    );
+    assert!(
+        apex > rest.y + 0.5,
+        "apex {apex} barely above rest {}",
+        rest.y
+    );
+
+    let end = translation(&app, controller);
+    assert!(grounded(&app, controller));
+    assert!((end.y - rest.y).abs() < 0.02, "landed at {}", end.y);
+    assert!(
+        (end - rest).reject_from(Vec3::Y).length() < 0.01,
+        "drifted {:?} while jumping in place",
+        end - rest
+    );
+}
+
+#[test]
+fn ride_moving_platform() {
+    let mut app = app();
+    let platform = app
+        .world
+        .spawn((
+            RigidBody::KinematicVelocityBased,
+            Collider::cuboid(0.5, 0.5, 0.5),
+            Velocity::linear(Vec3::ZERO),
+            TransformBundle::from_transform(Transform {
+                translation: Vec3::new(0.0, -0.05, 0.0),
+                scale: Vec3::new(3.0, 0.1, 3.0),
+                ..default()
+            }),
+        ))
+        .id();
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    run(&mut app, 120);
+
+    let controller_start = translation(&app, controller);
+    let platform_start = translation(&app, platform);
+    app.world.get_mut::<Velocity>(platform).unwrap().linvel = Vec3::new(1.0, 0.0, 0.0);
+    run(&mut app, 60);
+
+    let carried = translation(&app, controller) - controller_start;
+    let moved = translation(&app, platform) - platform_start;
+    assert!(grounded(&app, controller));
+    assert!(moved.x > 0.9);
+    assert!(
+        (carried - moved).length() < 0.1,
+        "controller moved {carried:?} while the platform moved {moved:?}"
+    );
+}
+
+fn single_step(app: &mut App, height: f32) -> Entity {
+    spawn_box(
+        app,
+        Transform {
+            translation: Vec3::new(2.5, height / 2.0, 0.0),
+            scale: Vec3::new(3.0, height, 5.0),
+            ..default()
+        },
+    )
+}
+
+#[test]
+fn step_up_onto_tall_step() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let step = single_step(&mut app, 0.35);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    app.world.entity_mut(controller).insert(StepUp::default());
+    run(&mut app, 60);
+
+    set_input(&mut app, controller, Vec3::X, false);
+    let mut reader = ManualEventReader::<SteppedUp>::default();
+    let mut events = Vec::new();
+    for _ in 0..60 {
+        app.update();
+        events.extend(
+            reader
+                .iter(app.world.resource::<Events<SteppedUp>>())
+                .copied(),
+        );
+    }
+
+    let end = translation(&app, controller);
+    let top = 0.35 + rest_height(&app, controller);
+    assert!(end.x > 1.5, "stuck in front of the step at {end:?}");
+    assert!(
+        (end.y - top).abs() < 0.05,
+        "standing at {} instead of {top}",
+        end.y
+    );
+    assert!(grounded(&app, controller));
+
+    assert_eq!(events.len(), 1, "expected one step, got {events:?}");
+    assert_eq!(events[0].entity, controller);
+    assert_eq!(events[0].step, step);
+    assert!((events[0].height - 0.35).abs() < 0.02);
+}
+
+#[test]
+fn reject_step_taller_than_max_step_height() {
+    let mut app = app();
+    flat_ground(&mut app);
+    single_step(&mut app, 0.6);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    app.world.entity_mut(controller).insert(StepUp::default());
+    run(&mut app, 60);
+    let rest = translation(&app, controller);
+
+    set_input(&mut app, controller, Vec3::X, false);
+    let mut reader = ManualEventReader::<SteppedUp>::default();
+    let mut stepped = 0;
+    for _ in 0..60 {
+        app.update();
+        stepped += reader
+            .iter(app.world.resource::<Events<SteppedUp>>())
+            .count();
+    }
+
+    let end = translation(&app, controller);
+    assert_eq!(stepped, 0);
+    assert!(end.x < 1.0, "passed through the step to {end:?}");
+    assert!((end.y - rest.y).abs() < 0.05, "climbed to {}", end.y);
+}
+
+/// Run off a crest onto a steep downhill ramp, returning how many ticks were spent airborne.
+fn run_over_crest(app: &mut App, controller: Entity) -> usize {
+    spawn_box(
+        app,
+        Transform {
+            translation: Vec3::new(-5.0, -0.5, 0.0),
+            scale: Vec3::new(10.0, 1.0, 5.0),
+            ..default()
+        },
+    );
+    // Ramp going down from the edge of the flat ground at the origin.
+    let rotation = Quat::from_rotation_z(-35f32.to_radians());
+    let scale = Vec3::new(40.0, 1.0, 5.0);
+    spawn_box(
+        app,
+        Transform {
+            translation: -(rotation * Vec3::new(-scale.x / 2.0, scale.y / 2.0, 0.0)),
+            rotation,
+            scale,
+        },
+    );
+    app.world.get_mut::<Movement>(controller).unwrap().max_speed = 8.0;
+    run(app, 60);
+
+    set_input(app, controller, Vec3::X, false);
+    let mut airborne_ticks = 0;
+    for _ in 0..60 {
+        app.update();
+        if !grounded(app, controller) {
+            airborne_ticks += 1;
+        }
+    }
+    airborne_ticks
+}
+
+#[test]
+fn launch_off_crest_without_ground_snap() {
+    let mut app = app();
+    let controller = spawn_controller(&mut app, Vec3::new(-3.0, 1.0, 0.0));
+    assert!(run_over_crest(&mut app, controller) > 0);
+}
+
+#[test]
+fn ground_snap_hugs_downhill_ramp() {
+    let mut app = app();
+    let controller = spawn_controller(&mut app, Vec3::new(-3.0, 1.0, 0.0));
+    app.world
+        .entity_mut(controller)
+        .insert(GroundSnap::default());
+
+    let airborne_ticks = run_over_crest(&mut app, controller);
+    assert_eq!(airborne_ticks, 0, "left the ground despite snapping");
+}
+
+#[test]
+fn ground_snap_does_not_hold_back_jumps() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    app.world
+        .entity_mut(controller)
+        .insert(GroundSnap::default());
+    run(&mut app, 60);
+    let rest = translation(&app, controller);
+
+    set_input(&mut app, controller, Vec3::X, true);
+    let mut apex = rest.y;
+    for _ in 0..60 {
+        app.update();
+        apex = apex.max(translation(&app, controller).y);
+    }
+
+    assert!(
+        apex > rest.y + 0.5,
+        "apex {apex} barely above rest {}",
+        rest.y
+    );
+}
+
+#[test]
+fn detect_wall_while_walking_along_it() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let wall = spawn_box(
+        &mut app,
+        Transform {
+            translation: Vec3::new(0.0, 1.5, 0.9),
+            scale: Vec3::new(20.0, 3.0, 1.0),
+            ..default()
+        },
+    );
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    app.world
+        .entity_mut(controller)
+        .insert(WallCaster::default());
+    run(&mut app, 60);
+
+    set_input(&mut app, controller, Vec3::X, false);
+    run(&mut app, 30);
+    let cast = app.world.get::<WallCast>(controller).unwrap();
+    assert!(cast.touching_wall());
+    let found = cast.current().unwrap();
+    assert_eq!(found.entity, wall);
+    assert!(
+        found.cast.normal.dot(-Vec3::Z) > 0.99,
+        "{:?}",
+        found.cast.normal
+    );
+
+    set_input(&mut app, controller, -Vec3::Z, false);
+    run(&mut app, 30);
+    let cast = app.world.get::<WallCast>(controller).unwrap();
+    assert!(!cast.touching_wall());
+    assert_eq!(cast.last().unwrap().entity, wall);
+}
+
+/// Launch the controller along a tall wall, returning how far it fell in half a second.
+fn fall_along_wall(app: &mut App, controller: Entity) -> f32 {
+    spawn_box(
+        app,
+        Transform {
+            translation: Vec3::new(10.0, 5.0, 0.9),
+            scale: Vec3::new(30.0, 10.0, 1.0),
+            ..default()
+        },
+    );
+    app.world.get_mut::<Velocity>(controller).unwrap().linvel = Vec3::new(6.0, 0.0, 0.0);
+    set_input(app, controller, Vec3::X, false);
+    let start = translation(app, controller);
+    run(app, 30);
+    start.y - translation(app, controller).y
+}
+
+#[test]
+fn fall_along_wall_without_wall_run() {
+    let mut app = app();
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 5.0, 0.0));
+    assert!(fall_along_wall(&mut app, controller) > 1.0);
+}
+
+#[test]
+fn wall_run_reduces_gravity_and_follows_wall() {
+    let mut app = app();
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 5.0, 0.0));
+    app.world.entity_mut(controller).insert(WallRun {
+        max_duration: None,
+        ..default()
+    });
+
+    let fallen = fall_along_wall(&mut app, controller);
+    assert!(fallen < 0.5, "fell {fallen} while wall running");
+
+    let wall_run = app.world.get::<WallRun>(controller).unwrap();
+    assert!(wall_run.running);
+    assert!(wall_run.direction.dot(Vec3::X) > 0.99);
+    let velocity = app.world.get::<ControllerVelocity>(controller).unwrap();
+    assert!(
+        velocity.linear.x > 4.0,
+        "slowed down to {:?}",
+        velocity.linear
+    );
+    assert!(app
+        .world
+        .get::<WallCast>(controller)
+        .unwrap()
+        .touching_wall());
+}
+
+#[test]
+fn wall_run_ends_after_max_duration() {
+    let mut app = app();
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 5.0, 0.0));
+    app.world.entity_mut(controller).insert(WallRun {
+        max_duration: Some(0.2),
+        timer: 0.2,
+        ..default()
+    });
+
+    fall_along_wall(&mut app, controller);
+    let wall_run = app.world.get::<WallRun>(controller).unwrap();
+    assert!(!wall_run.running);
+    assert_eq!(wall_run.timer, 0.0);
+}
+
+#[test]
+fn wall_jump_pushes_away_from_wall() {
+    let mut app = app();
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 5.0, 0.0));
+    app.world.entity_mut(controller).insert(WallJump::default());
+    fall_along_wall(&mut app, controller);
+    assert!(app
+        .world
+        .get::<WallCast>(controller)
+        .unwrap()
+        .touching_wall());
+    let before = app
+        .world
+        .get::<ControllerVelocity>(controller)
+        .unwrap()
+        .linear;
+
+    set_input(&mut app, controller, Vec3::X, true);
+    run(&mut app, 2);
+
+    let after = app
+        .world
+        .get::<ControllerVelocity>(controller)
+        .unwrap()
+        .linear;
+    assert!(after.z < -1.0, "not pushed off the wall: {after:?}");
+    assert!(after.y > 1.0, "not pushed upwards: {after:?}");
+    assert!(
+        (after.x - before.x).abs() < 0.5,
+        "lost speed along the wall: {before:?} -> {after:?}"
+    );
+
+    let wall_jump = app.world.get::<WallJump>(controller).unwrap();
+    assert_eq!(wall_jump.remaining_jumps, 0);
+    assert!(wall_jump.last_wall.is_some());
+}
+
+/// Drop the controller down a wall with a ledge at `y = 2` until it hangs from it.
+fn hang_from_ledge(app: &mut App) -> Entity {
+    flat_ground(app);
+    spawn_box(
+        app,
+        Transform {
+            translation: Vec3::new(0.0, 1.0, 2.0),
+            scale: Vec3::new(10.0, 2.0, 3.0),
+            ..default()
+        },
+    );
+    let controller = spawn_controller(app, Vec3::new(0.0, 1.7, 0.15));
+    app.world
+        .entity_mut(controller)
+        .insert(LedgeGrab::default());
+    set_input(app, controller, Vec3::Z, false);
+    run(app, 60);
+    set_input(app, controller, Vec3::ZERO, false);
+    run(app, 30);
+    controller
+}
+
+#[test]
+fn grab_and_hang_from_ledge() {
+    let mut app = app();
+    let controller = hang_from_ledge(&mut app);
+
+    let ledge_grab = app.world.get::<LedgeGrab>(controller).unwrap();
+    let LedgeState::Hanging(ledge) = ledge_grab.state else {
+        panic!("not hanging: {:?}", ledge_grab.state);
+    };
+    assert!((ledge.point.y - 2.0).abs() < 0.01);
+    assert!(ledge.normal.dot(-Vec3::Z) > 0.99);
+
+    let start = translation(&app, controller);
+    let expected = 2.0 - ledge_grab.hang_height;
+    assert!((start.y - expected).abs() < 0.05, "hanging at {start}");
+    run(&mut app, 60);
+    let end = translation(&app, controller);
+    assert!(end.distance(start) < 0.02, "drifted {start} -> {end}");
+}
+
+#[test]
+fn shimmy_along_ledge() {
+    let mut app = app();
+    let controller = hang_from_ledge(&mut app);
+    let start = translation(&app, controller);
+
+    set_input(&mut app, controller, Vec3::X, false);
+    run(&mut app, 30);
+    let end = translation(&app, controller);
+    assert!(app.world.get::<LedgeGrab>(controller).unwrap().hanging());
+    assert!(end.x - start.x > 0.5, "shimmied {start} -> {end}");
+    assert!(
+        (end.y - start.y).abs() < 0.05,
+        "height changed {start} -> {end}"
+    );
+}
+
+#[test]
+fn drop_off_ledge() {
+    let mut app = app();
+    let controller = hang_from_ledge(&mut app);
+
+    set_input(&mut app, controller, -Vec3::Z, false);
+    run(&mut app, 30);
+    assert!(!app.world.get::<LedgeGrab>(controller).unwrap().hanging());
+    assert!(translation(&app, controller).y < 1.0);
+}
+
+#[test]
+fn climb_up_ledge() {
+    let mut app = app();
+    let controller = hang_from_ledge(&mut app);
+
+    set_input(&mut app, controller, Vec3::ZERO, true);
+    run(&mut app, 1);
+    assert!(app.world.get::<LedgeGrab>(controller).unwrap().climbing());
+    set_input(&mut app, controller, Vec3::ZERO, false);
+    run(&mut app, 120);
+
+    let ledge_grab = app.world.get::<LedgeGrab>(controller).unwrap();
+    assert_eq!(ledge_grab.state, LedgeState::None);
+    let end = translation(&app, controller);
+    assert!(grounded(&app, controller));
+    assert!(end.z > 0.5, "not on top of the ledge: {end}");
+    assert!(
+        (end.y - 2.0 - rest_height(&app, controller)).abs() < 0.05,
+        "not standing on the ledge: {end}"
+    );
+}
+
+#[test]
+fn climbing_ledge_does_not_jump() {
+    let mut app = app();
+    let controller = hang_from_ledge(&mut app);
+    // Leave a spare air jump that pressing jump could use up.
+    let mut jump = app.world.get_mut::<Jump>(controller).unwrap();
+    jump.jumps = 2;
+    jump.remaining_jumps = 1;
+
+    let mut jumped = current_reader::<Jumped>(&app);
+    set_input(&mut app, controller, Vec3::ZERO, true);
+    run(&mut app, 1);
+    assert!(read_events(&app, &mut jumped).is_empty());
+    set_input(&mut app, controller, Vec3::ZERO, false);
+    run(&mut app, 10);
+
+    assert!(app.world.get::<LedgeGrab>(controller).unwrap().climbing());
+    assert_eq!(
+        app.world.get::<Jump>(controller).unwrap().remaining_jumps,
+        1
+    );
+}
+
+/// Walk into an obstacle `height` tall and press jump, returning how many
+/// [`MantleStarted`], [`MantleEnded`] and [`Jumped`] events were sent.
+fn mantle_onto(app: &mut App, controller: Entity, height: f32) -> (usize, usize, usize) {
+    spawn_box(
+        app,
+        Transform {
+            translation: Vec3::new(6.0, height / 2.0, 0.0),
+            scale: Vec3::new(10.0, height, 5.0),
+            ..default()
+        },
+    );
+    run(app, 60);
+
+    let mut started = ManualEventReader::<MantleStarted>::default();
+    let mut ended = ManualEventReader::<MantleEnded>::default();
+    let mut jumped = current_reader::<Jumped>(app);
+    let mut counts = (0, 0, 0);
+    for tick in 0..60 {
+        set_input(app, controller, Vec3::X, tick < 5);
+        app.update();
+        counts.0 += started
+            .iter(app.world.resource::<Events<MantleStarted>>())
+            .count();
+        counts.1 += ended
+            .iter(app.world.resource::<Events<MantleEnded>>())
+            .count();
+        counts.2 += read_events(app, &mut jumped).len();
+    }
+    counts
+}
+
+#[test]
+fn mantle_onto_waist_high_obstacle() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.5, 1.0, 0.0));
+    app.world.entity_mut(controller).insert(Mantle::default());
+
+    // The jump that starts the mantle is used up by it.
+    assert_eq!(mantle_onto(&mut app, controller, 1.0), (1, 1, 0));
+    assert!(!app.world.get::<Mantle>(controller).unwrap().mantling());
+    set_input(&mut app, controller, Vec3::ZERO, false);
+    run(&mut app, 30);
+
+    let end = translation(&app, controller);
+    assert!(grounded(&app, controller));
+    assert!(end.x > 1.0, "not on top of the obstacle: {end}");
+    assert!(
+        (end.y - 1.0 - rest_height(&app, controller)).abs() < 0.05,
+        "not standing on the obstacle: {end}"
+    );
+}
+
+#[test]
+fn jump_instead_of_mantling_tall_obstacle() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.5, 1.0, 0.0));
+    app.world.entity_mut(controller).insert(Mantle::default());
+
+    assert_eq!(mantle_onto(&mut app, controller, 2.5), (0, 0, 1));
+    assert!(translation(&app, controller).x < 1.0);
+}
+
+fn set_crouching(app: &mut App, entity: Entity, crouching: bool) {
+    app.world
+        .get_mut::<ControllerInput>(entity)
+        .unwrap()
+        .crouching = crouching;
+}
+
+/// Returns the controller and the height it was standing at before crouching.
+fn spawn_crouching_controller(app: &mut App) -> (Entity, f32) {
+    flat_ground(app);
+    let controller = spawn_controller(app, Vec3::new(0.0, 1.0, 0.0));
+    app.world
+        .entity_mut(controller)
+        .insert((Crouch::default(), CrouchShapes::<Collider>::default()));
+    run(app, 60);
+    let standing = translation(app, controller).y;
+    set_crouching(app, controller, true);
+    run(app, 60);
+    (controller, standing)
+}
+
+#[test]
+fn crouch_and_stand_up() {
+    let mut app = app();
+    let (controller, standing) = spawn_crouching_controller(&mut app);
+
+    assert!(app.world.get::<Crouch>(controller).unwrap().crouching);
+    assert_eq!(app.world.get::<Float>(controller).unwrap().distance, 0.45);
+    assert_eq!(
+        app.world.get::<Movement>(controller).unwrap().max_speed,
+        Movement::default().max_speed * 0.5
+    );
+    let crouched = translation(&app, controller).y;
+    assert!(grounded(&app, controller));
+    assert!(
+        (crouched - rest_height(&app, controller)).abs() < 0.02,
+        "crouching at {crouched}"
+    );
+
+    set_crouching(&mut app, controller, false);
+    run(&mut app, 60);
+    assert!(!app.world.get::<Crouch>(controller).unwrap().crouching);
+    assert_eq!(
+        app.world.get::<Movement>(controller).unwrap().max_speed,
+        Movement::default().max_speed
+    );
+    let stood = translation(&app, controller).y;
+    assert!(
+        (stood - standing).abs() < 0.02,
+        "standing at {stood}, expected {standing}"
+    );
+}
+
+#[test]
+fn changes_while_crouched_carry_over() {
+    let mut app = app();
+    let (controller, _) = spawn_crouching_controller(&mut app);
+    app.world.get_mut::<Movement>(controller).unwrap().max_speed *= 2.0;
+    app.world.get_mut::<Float>(controller).unwrap().distance += 0.1;
+
+    set_crouching(&mut app, controller, false);
+    run(&mut app, 1);
+    assert!(!app.world.get::<Crouch>(controller).unwrap().crouching);
+    assert_eq!(
+        app.world.get::<Movement>(controller).unwrap().max_speed,
+        Movement::default().max_speed * 2.0
+    );
+    let distance = app.world.get::<Float>(controller).unwrap().distance;
+    assert!((distance - Float::default().distance - 0.1).abs() < 1e-5);
+}
+
+#[test]
+fn ceiling_blocks_standing_up() {
+    let mut app = app();
+    let (controller, _) = spawn_crouching_controller(&mut app);
+    let crouched = translation(&app, controller).y;
+    let ceiling = spawn_box(
+        &mut app,
+        Transform::from_translation(Vec3::new(0.0, 1.5, 0.0)),
+    );
+    // Let the ceiling make it into the physics world.
+    run(&mut app, 1);
+
+    set_crouching(&mut app, controller, false);
+    run(&mut app, 30);
+    let crouch = app.world.get::<Crouch>(controller).unwrap();
+    assert!(crouch.crouching);
+    assert!(crouch.blocked);
+    assert!((translation(&app, controller).y - crouched).abs() < 0.02);
+
+    app.world.despawn(ceiling);
+    run(&mut app, 60);
+    let crouch = app.world.get::<Crouch>(controller).unwrap();
+    assert!(!crouch.crouching);
+    assert!(!crouch.blocked);
+    assert!(translation(&app, controller).y > crouched + 0.05);
+}
+
+#[test]
+fn head_bump_ends_jump() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let ceiling = spawn_box(
+        &mut app,
+        Transform::from_translation(Vec3::new(0.0, 2.0, 0.0)),
+    );
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 0.6, 0.0));
+    run(&mut app, 120);
+
+    set_input(&mut app, controller, Vec3::ZERO, true);
+    let mut reader = ManualEventReader::<HeadBump>::default();
+    let mut bumps = Vec::new();
+    let mut landed = false;
+    for _ in 0..120 {
+        app.update();
+        let new = reader
+            .iter(app.world.resource::<Events<HeadBump>>())
+            .copied()
+            .collect::<Vec<_>>();
+        if !new.is_empty() {
+            assert!(!app.world.get::<Jump>(controller).unwrap().jumping());
+        }
+        bumps.extend(new);
+        landed |= !bumps.is_empty() && grounded(&app, controller);
+    }
+
+    assert_eq!(bumps.len(), 1);
+    assert_eq!(bumps[0].entity, controller);
+    assert_eq!(bumps[0].ceiling, ceiling);
+    assert!(bumps[0].speed > 0.0);
+    assert!(landed, "never came back down");
+}
+
+fn read_events<E: Event + Copy>(app: &App, reader: &mut ManualEventReader<E>) -> Vec<E> {
+    reader
+        .iter(app.world.resource::<Events<E>>())
+        .copied()
+        .collect()
+}
+
+/// Reader that skips events that have already been sent.
+fn current_reader<E: Event>(app: &App) -> ManualEventReader<E> {
+    app.world.resource::<Events<E>>().get_reader_current()
+}
+
+#[test]
+fn jump_sends_lifecycle_events() {
+    let mut app = app();
+    let floor = flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    run(&mut app, 180);
+
+    let mut jumped = current_reader::<Jumped>(&app);
+    let mut left = current_reader::<LeftGround>(&app);
+    let mut landed = current_reader::<Landed>(&app);
+    let mut order = Vec::new();
+    set_input(&mut app, controller, Vec3::ZERO, true);
+    for _ in 0..120 {
+        app.update();
+        for event in read_events(&app, &mut jumped) {
+            assert_eq!(event.entity, controller);
+            assert_eq!(event.jump_index, 1);
+            assert_eq!(event.wall, None);
+            order.push("jumped");
+        }
+        for event in read_events(&app, &mut left) {
+            if !order.contains(&"left") {
+                assert_eq!(event.ground, Some(floor));
+                assert!(event.jumped);
+                order.push("left");
+            }
+        }
+        for event in read_events(&app, &mut landed) {
+            if !order.contains(&"landed") {
+                assert_eq!(event.ground, floor);
+                assert!(
+                    event.impact_velocity.y < -1.0,
+                    "{:?}",
+                    event.impact_velocity
+                );
+                order.push("landed");
+            }
+        }
+    }
+
+    assert_eq!(order, ["jumped", "left", "landed"]);
+}
+
+#[test]
+fn walk_off_ledge_sends_lifecycle_events() {
+    let mut app = app();
+    let floor = flat_ground(&mut app);
+    let platform = spawn_box(
+        &mut app,
+        Transform {
+            translation: Vec3::new(0.0, 1.0, 0.0),
+            scale: Vec3::new(2.0, 2.0, 4.0),
+            ..default()
+        },
+    );
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 3.0, 0.0));
+    run(&mut app, 180);
+
+    let mut left = current_reader::<LeftGround>(&app);
+    let mut coyote = current_reader::<CoyoteExpired>(&app);
+    let mut changed = current_reader::<GroundEntityChanged>(&app);
+    let mut landed = current_reader::<Landed>(&app);
+    let (mut lefts, mut coyotes, mut changes, mut landings) = (vec![], vec![], vec![], vec![]);
+    set_input(&mut app, controller, Vec3::X, false);
+    for _ in 0..90 {
+        app.update();
+        lefts.extend(read_events(&app, &mut left));
+        coyotes.extend(read_events(&app, &mut coyote));
+        changes.extend(read_events(&app, &mut changed));
+        landings.extend(read_events(&app, &mut landed));
+    }
+
+    // Grounded can flicker while settling after landing, so only the first of each
+    // transition is checked.
+    assert_eq!(lefts[0].ground, Some(platform));
+    assert!(!lefts[0].jumped);
+    assert!(!coyotes.is_empty());
+    assert!(changes
+        .iter()
+        .any(|change| change.previous == Some(platform)));
+    assert_eq!(changes.last().unwrap().ground, Some(floor));
+    assert_eq!(landings.last().unwrap().ground, floor);
+    assert_eq!(
+        translation(&app, controller).y.round(),
+        0.0,
+        "{}",
+        translation(&app, controller)
+    );
+}
+
+#[test]
+fn unstable_ground_sends_started_slipping() {
+    let mut app = app();
+    let slope = spawn_box(
+        &mut app,
+        Transform {
+            rotation: Quat::from_rotation_z(50f32.to_radians()),
+            scale: Vec3::new(12.0, 1.0, 4.0),
+            ..default()
+        },
+    );
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.5, 0.0));
+
+    let mut reader = ManualEventReader::<StartedSlipping>::default();
+    let mut slipping = Vec::new();
+    for _ in 0..60 {
+        app.update();
+        slipping.extend(read_events(&app, &mut reader));
+    }
+    assert_eq!(slipping.len(), 1);
+    assert_eq!(slipping[0].entity, controller);
+    assert_eq!(slipping[0].ground, slope);
+}
+
+#[test]
+fn high_fall_deals_fall_damage() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let high = spawn_controller(&mut app, Vec3::new(0.0, 12.0, 0.0));
+    let low = spawn_controller(&mut app, Vec3::new(5.0, 1.5, 0.0));
+    app.world.entity_mut(high).insert(FallDamage::default());
+    app.world.entity_mut(low).insert(FallDamage::default());
+
+    let mut landed_reader = ManualEventReader::<Landed>::default();
+    let mut damaged_reader = ManualEventReader::<FallDamaged>::default();
+    let (mut impacts, mut damaged) = (vec![], vec![]);
+    for _ in 0..180 {
+        app.update();
+        impacts.extend(read_events(&app, &mut landed_reader));
+        damaged.extend(read_events(&app, &mut damaged_reader));
+    }
+
+    let high_impact = impacts.iter().find(|impact| impact.entity == high).unwrap();
+    assert!(high_impact.vertical_velocity < -10.0, "{high_impact:?}");
+    assert!(
+        (high_impact.fall_height - 11.0).abs() < 1.0,
+        "{high_impact:?}"
+    );
+    assert!(impacts.iter().any(|impact| impact.entity == low));
+
+    assert_eq!(damaged.len(), 1);
+    assert_eq!(damaged[0].entity, high);
+    assert!(damaged[0].damage > 0.0 && damaged[0].damage < 100.0);
+}
+
+#[test]
+fn fall_height_below_origin() {
+    let mut app = app();
+    spawn_box(
+        &mut app,
+        Transform {
+            translation: Vec3::new(0.0, -20.5, 0.0),
+            scale: Vec3::new(50.0, 1.0, 50.0),
+            ..default()
+        },
+    );
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, -8.0, 0.0));
+
+    let mut landed_reader = ManualEventReader::<Landed>::default();
+    let mut impacts = vec![];
+    for _ in 0..180 {
+        app.update();
+        impacts.extend(read_events(&app, &mut landed_reader));
+    }
+
+    let impact = impacts
+        .iter()
+        .find(|impact| impact.entity == controller)
+        .unwrap();
+    assert!((impact.fall_height - 11.0).abs() < 1.0, "{impact:?}");
+}
+
+#[test]
+fn conveyor_surface_carries_controller() {
+    let mut app = app();
+    let floor = flat_ground(&mut app);
+    app.world.entity_mut(floor).insert(SurfaceProperties {
+        conveyor_velocity: Vec3::new(2.0, 0.0, 0.0),
+        ..default()
+    });
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    run(&mut app, 120);
+
+    let ground = app.world.get::<ViableGroundCast>(controller).unwrap();
+    assert_eq!(ground.current().unwrap().surface.conveyor_velocity.x, 2.0);
+
+    let start = translation(&app, controller);
+    run(&mut app, 60);
+    let travelled = translation(&app, controller) - start;
+    assert!((travelled.x - 2.0).abs() < 0.2, "{travelled}");
+    assert!(travelled.z.abs() < 0.05, "{travelled}");
+}
+
+#[test]
+fn surface_multipliers_scale_speed_and_jump() {
+    let mut app = app();
+    flat_ground(&mut app);
+    // Thin pads raised slightly above the floor, one plain and one sticky and bouncy.
+    let pad = |translation| Transform {
+        translation,
+        scale: Vec3::new(20.0, 0.1, 4.0),
+        ..default()
+    };
+    spawn_box(&mut app, pad(Vec3::new(0.0, 0.05, 0.0)));
+    let mud = spawn_box(&mut app, pad(Vec3::new(0.0, 0.05, 10.0)));
+    app.world.entity_mut(mud).insert(SurfaceProperties {
+        speed_multiplier: 0.5,
+        jump_multiplier: 1.5,
+        ..default()
+    });
+
+    let normal = spawn_controller(&mut app, Vec3::new(-8.0, 1.0, 0.0));
+    let muddy = spawn_controller(&mut app, Vec3::new(-8.0, 1.0, 10.0));
+    run(&mut app, 120);
+
+    for controller in [normal, muddy] {
+        set_input(&mut app, controller, Vec3::X, false);
+    }
+    run(&mut app, 90);
+    let normal_speed = velocity(&app, normal).x;
+    let muddy_speed = velocity(&app, muddy).x;
+    assert!((normal_speed - 5.0).abs() < 0.3, "{normal_speed}");
+    assert!((muddy_speed - 2.5).abs() < 0.3, "{muddy_speed}");
+
+    for controller in [normal, muddy] {
+        set_input(&mut app, controller, Vec3::ZERO, true);
+    }
+    let (mut normal_peak, mut muddy_peak) = (0.0f32, 0.0f32);
+    for _ in 0..90 {
+        app.update();
+        normal_peak = normal_peak.max(translation(&app, normal).y);
+        muddy_peak = muddy_peak.max(translation(&app, muddy).y);
+    }
+    assert!(
+        muddy_peak > normal_peak + 0.5,
+        "{muddy_peak} vs {normal_peak}"
+    );
+}
+
+#[test]
+fn friction_settings_control_braking() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let braking = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    let gliding = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 5.0));
+    app.world.entity_mut(gliding).insert(FrictionSettings {
+        ground_strength: 0.0,
+        ..default()
+    });
+    run(&mut app, 120);
+
+    for controller in [braking, gliding] {
+        set_input(&mut app, controller, Vec3::X, false);
+    }
+    run(&mut app, 60);
+    for controller in [braking, gliding] {
+        set_input(&mut app, controller, Vec3::ZERO, false);
+    }
+    run(&mut app, 30);
+
+    let braking_speed = velocity(&app, braking).x;
+    let gliding_speed = velocity(&app, gliding).x;
+    assert!(braking_speed.abs() < 0.5, "{braking_speed}");
+    assert!(gliding_speed > 4.0, "{gliding_speed}");
+}
+
+#[test]
+fn movement_profiles_pick_deceleration_and_turn_around() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let default_controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    let braking = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 5.0));
+    let snappy = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 10.0));
+    // No friction, so only the deceleration profile can stop it.
+    app.world.entity_mut(braking).insert(FrictionSettings {
+        ground_strength: 0.0,
+        ..default()
+    });
+    let mut movement = app.world.get_mut::<Movement>(braking).unwrap();
+    movement.ground.deceleration = SpeedCurve::new(Strength::Scaled(20.0));
+    let mut movement = app.world.get_mut::<Movement>(snappy).unwrap();
+    movement.ground.turn_around = SpeedCurve::new(Strength::Scaled(40.0));
+    run(&mut app, 120);
+
+    for controller in [default_controller, braking, snappy] {
+        set_input(&mut app, controller, Vec3::X, false);
+    }
+    run(&mut app, 60);
+
+    set_input(&mut app, braking, Vec3::ZERO, false);
+    for controller in [default_controller, snappy] {
+        set_input(&mut app, controller, Vec3::NEG_X, false);
+    }
+    run(&mut app, 2);
+
+    let default_speed = velocity(&app, default_controller).x;
+    let snappy_speed = velocity(&app, snappy).x;
+    assert!(
+        snappy_speed < default_speed - 1.0,
+        "{snappy_speed} vs {default_speed}"
+    );
+
+    run(&mut app, 28);
+    let braking_speed = velocity(&app, braking).x;
+    assert!(braking_speed.abs() < 0.5, "{braking_speed}");
+}
+
+#[test]
+fn air_control_limits_steering_and_keeps_momentum() {
+    let mut app = app();
+    let default_controller = spawn_controller(&mut app, Vec3::new(0.0, 30.0, 0.0));
+    let limited = spawn_controller(&mut app, Vec3::new(0.0, 30.0, 5.0));
+    app.world.entity_mut(limited).insert(AirControl {
+        acceleration_multiplier: 0.2,
+        max_speed: Some(2.0),
+        ..default()
+    });
+    let coasting = spawn_controller(&mut app, Vec3::new(0.0, 30.0, 10.0));
+    app.world.entity_mut(coasting).insert(AirControl {
+        preserve_momentum: true,
+        ..default()
+    });
+    let braking = spawn_controller(&mut app, Vec3::new(0.0, 30.0, 15.0));
+    run(&mut app, 1);
+
+    for controller in [default_controller, limited] {
+        set_input(&mut app, controller, Vec3::X, false);
+    }
+    for controller in [coasting, braking] {
+        app.world.get_mut::<Velocity>(controller).unwrap().linvel = Vec3::new(10.0, 0.0, 0.0);
+    }
+    run(&mut app, 30);
+
+    assert!(!grounded(&app, default_controller));
+    let default_speed = velocity(&app, default_controller).x;
+    let limited_speed = velocity(&app, limited).x;
+    assert!(limited_speed < 2.0 + 0.01, "{limited_speed}");
+    assert!(
+        limited_speed < default_speed,
+        "{limited_speed} vs {default_speed}"
+    );
+
+    let coasting_speed = velocity(&app, coasting).x;
+    let braking_speed = velocity(&app, braking).x;
+    assert!((coasting_speed - 10.0).abs() < 0.1, "{coasting_speed}");
+    assert!(braking_speed < 5.0, "{braking_speed}");
+}
+
+/// Jump with `profile`, holding jump for `held_ticks`, returning how high above its
+/// starting point the controller got and how many ticks that took.
+fn profile_jump(profile: JumpProfile, held_ticks: usize) -> (f32, usize) {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    app.world.entity_mut(controller).insert(profile);
+    run(&mut app, 120);
+
+    let start = translation(&app, controller).y;
+    let (mut peak, mut peak_tick) = (start, 0);
+    for tick in 1..=90 {
+        set_input(&mut app, controller, Vec3::ZERO, tick <= held_ticks);
+        app.update();
+        let height = translation(&app, controller).y;
+        if height > peak {
+            (peak, peak_tick) = (height, tick);
+        }
+    }
+    (peak - start, peak_tick)
+}
+
+#[test]
+fn jump_profile_reaches_apex_heights() {
+    let profile = JumpProfile {
+        min_height: 0.5,
+        max_height: 1.5,
+        time_to_apex: 0.4,
+    };
+
+    let (held_height, held_ticks) = profile_jump(profile.clone(), 90);
+    assert!((held_height - 1.5).abs() < 0.1, "{held_height}");
+    // 0.4 seconds at 60 ticks per second.
+    assert!((held_ticks as i32 - 24).abs() <= 2, "{held_ticks}");
+
+    let (tapped_height, _) = profile_jump(profile, 1);
+    assert!((tapped_height - 0.5).abs() < 0.1, "{tapped_height}");
+}
+
+#[test]
+fn degenerate_jump_profile_stays_finite() {
+    let profile = JumpProfile {
+        min_height: 2.0,
+        max_height: 1.0,
+        time_to_apex: 0.0,
+    };
+    let mass = ControllerMass {
+        mass: 1.0,
+        ..default()
+    };
+    for value in [
+        profile.initial_speed(),
+        profile.rise_acceleration(),
+        profile.release_acceleration(),
+        profile.gravity_multiplier(&Gravity::default()),
+        profile.initial_force(&mass, DT),
+    ] {
+        assert!(value.is_finite(), "{profile:?} gave {value}");
+    }
+    // `min_height` is clamped to `max_height`, so releasing early changes nothing.
+    let (release, rise) = (profile.release_acceleration(), profile.rise_acceleration());
+    assert!((release / rise - 1.0).abs() < 1e-4, "{release} != {rise}");
+}
+
+#[test]
+fn gravity_modifiers_shape_jump_arc() {
+    let modifiers = GravityModifiers::default();
+    assert_eq!(
+        modifiers.multiplier(-5.0, true, false),
+        modifiers.fall_multiplier
+    );
+    assert_eq!(
+        modifiers.multiplier(5.0, true, true),
+        modifiers.release_multiplier
+    );
+    assert_eq!(
+        modifiers.multiplier(0.5, true, false),
+        modifiers.apex_multiplier
+    );
+    assert_eq!(modifiers.multiplier(0.5, false, false), 1.0);
+    assert_eq!(modifiers.multiplier(5.0, true, false), 1.0);
+
+    let mut app = app();
+    flat_ground(&mut app);
+    let plain = spawn_controller(&mut app, Vec3::new(0.0, 20.0, 0.0));
+    let modified = spawn_controller(&mut app, Vec3::new(0.0, 20.0, 5.0));
+    app.world.entity_mut(modified).insert(modifiers);
+    run(&mut app, 60);
+    assert!(
+        translation(&app, modified).y < translation(&app, plain).y - 1.0,
+        "falling should be faster"
+    );
+
+    // Tapping jump cuts the jump shorter than holding it.
+    let held = spawn_controller(&mut app, Vec3::new(10.0, 1.0, 0.0));
+    let tapped = spawn_controller(&mut app, Vec3::new(10.0, 1.0, 5.0));
+    for controller in [held, tapped] {
+        app.world
+            .entity_mut(controller)
+            .insert(GravityModifiers::default());
+    }
+    run(&mut app, 120);
+
+    let (mut held_peak, mut tapped_peak) = (0.0f32, 0.0f32);
+    for tick in 0..90 {
+        set_input(&mut app, held, Vec3::ZERO, true);
+        set_input(&mut app, tapped, Vec3::ZERO, tick < 2);
+        app.update();
+        held_peak = held_peak.max(translation(&app, held).y);
+        tapped_peak = tapped_peak.max(translation(&app, tapped).y);
+    }
+    assert!(
+        tapped_peak < held_peak - 0.2,
+        "{tapped_peak} vs {held_peak}"
+    );
+}
+
+#[test]
+fn gravity_modifiers_do_not_slow_ledge_drops() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let mut controllers = vec![];
+    for z in [0.0, 10.0] {
+        spawn_box(
+            &mut app,
+            Transform {
+                translation: Vec3::new(0.0, 5.0, z),
+                scale: Vec3::new(2.0, 10.0, 4.0),
+                ..default()
+            },
+        );
+        controllers.push(spawn_controller(&mut app, Vec3::new(0.0, 12.0, z)));
+    }
+    let (plain, modified) = (controllers[0], controllers[1]);
+    // Only the apex hang is left, which should be reserved for jumps.
+    app.world.entity_mut(modified).insert(GravityModifiers {
+        fall_multiplier: 1.0,
+        ..default()
+    });
+    run(&mut app, 120);
+
+    for controller in [plain, modified] {
+        set_input(&mut app, controller, Vec3::X, false);
+    }
+    for _ in 0..90 {
+        app.update();
+        let (plain_y, modified_y) = (translation(&app, plain).y, translation(&app, modified).y);
+        assert!(
+            (plain_y - modified_y).abs() < 0.05,
+            "{modified_y} vs {plain_y}"
+        );
+    }
+    assert!(translation(&app, modified).y < 5.0);
+}
+
+#[test]
+fn air_drag_limits_fall_speed() {
+    let mut app = app();
+    let plain = spawn_controller(&mut app, Vec3::new(0.0, 100.0, 0.0));
+    let capped = spawn_controller(&mut app, Vec3::new(0.0, 100.0, 5.0));
+    app.world.entity_mut(capped).insert(AirDrag {
+        terminal_velocity: Some(10.0),
+        quadratic_drag: 0.0,
+    });
+    // Drag alone settles at sqrt(9.817 / 0.1) ≈ 9.9.
+    let dragged = spawn_controller(&mut app, Vec3::new(0.0, 100.0, 10.0));
+    app.world.entity_mut(dragged).insert(AirDrag {
+        terminal_velocity: None,
+        quadratic_drag: 0.1,
+    });
+    run(&mut app, 120);
+
+    let plain_speed = velocity(&app, plain).y;
+    let capped_speed = velocity(&app, capped).y;
+    let dragged_speed = velocity(&app, dragged).y;
+    assert!(plain_speed < -15.0, "{plain_speed}");
+    assert!((capped_speed + 10.0).abs() < 0.05, "{capped_speed}");
+    assert!(
+        dragged_speed < -8.0 && dragged_speed > -10.0,
+        "{dragged_speed}"
+    );
+}
+
+fn spawn_gravity_field(app: &mut App, collider: Collider, field: GravityField, at: Vec3) -> Entity {
+    app.world
+        .spawn((
+            collider,
+            Sensor,
+            field,
+            TransformBundle::from_transform(Transform::from_translation(at)),
+        ))
+        .id()
+}
+
+#[test]
+fn gravity_fields_set_controller_gravity() {
+    let mut app = app();
+    // Planetoid with its own gravity well.
+    app.world.spawn((
+        Collider::ball(5.0),
+        TransformBundle::from_transform(Transform::IDENTITY),
+    ));
+    let well = spawn_gravity_field(
+        &mut app,
+        Collider::ball(20.0),
+        GravityField {
+            shape: GravityFieldShape::Point,
+            ..default()
+        },
+        Vec3::ZERO,
+    );
+    // A zone inside the well that overrides it.
+    let zone = spawn_gravity_field(
+        &mut app,
+        Collider::cuboid(2.0, 2.0, 2.0),
+        GravityField {
+            shape: GravityFieldShape::Directional { up: Vec3::Z },
+            acceleration: -5.0,
+            priority: 1,
+            ..default()
+        },
+        Vec3::new(0.0, 0.0, 12.0),
+    );
+
+    let outside = Gravity {
+        acceleration: -9.817,
+        up_vector: Vec3::Y,
+    };
+    let receiver = || GravityFieldReceiver {
+        outside: Some(outside.clone()),
+        ..default()
+    };
+    let orbiting = spawn_controller(&mut app, Vec3::new(9.0, 0.0, 0.0));
+    let zoned = spawn_controller(&mut app, Vec3::new(0.0, 0.0, 12.0));
+    let far = spawn_controller(&mut app, Vec3::new(50.0, 0.0, 0.0));
+    for controller in [orbiting, zoned, far] {
+        app.world.entity_mut(controller).insert(receiver());
+        app.world.get_mut::<Gravity>(controller).unwrap().up_vector = Vec3::NEG_Y;
+    }
+    run(&mut app, 2);
+
+    let gravity = |app: &App, entity| app.world.get::<Gravity>(entity).unwrap().clone();
+    let fields = |app: &App, entity| {
+        app.world
+            .get::<GravityFieldReceiver>(entity)
+            .unwrap()
+            .fields
+            .clone()
+    };
+
+    assert!(gravity(&app, orbiting).up_vector.abs_diff_eq(Vec3::X, 0.01));
+    assert_eq!(fields(&app, orbiting), [well]);
+
+    let zoned_gravity = gravity(&app, zoned);
+    assert!(zoned_gravity.up_vector.abs_diff_eq(Vec3::Z, 0.01));
+    assert!((zoned_gravity.acceleration + 5.0).abs() < 0.01);
+    assert_eq!(fields(&app, zoned), [zone]);
+
+    assert_eq!(gravity(&app, far).up_vector, Vec3::Y);
+    assert!(fields(&app, far).is_empty());
+
+    // Gravity only counts as changed when a field actually changes it.
+    let last_changed = |app: &App, entity| {
+        app.world
+            .entity(entity)
+            .get_ref::<Gravity>()
+            .unwrap()
+            .last_changed()
+    };
+    let before = [zoned, far].map(|entity| last_changed(&app, entity));
+    run(&mut app, 2);
+    assert_eq!(
+        [zoned, far].map(|entity| last_changed(&app, entity)),
+        before
+    );
+
+    // Falls towards the planetoid rather than down.
+    run(&mut app, 60);
+    let position = translation(&app, orbiting);
+    assert!(position.x < 8.0, "{position}");
+    assert!(position.y.abs() < 0.1, "{position}");
+}
+
+#[test]
+fn up_vector_reorients_smoothly() {
+    let mut app = app();
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 10.0, 0.0));
+    app.world.entity_mut(controller).insert(UpReorientation {
+        duration: 0.5,
+        ..default()
+    });
+    app.world
+        .get_mut::<Upright>(controller)
+        .unwrap()
+        .forward_vector = Some(Vec3::NEG_Z);
+    run(&mut app, 1);
+
+    // Flip gravity sideways, a quarter turn should take a quarter of a second.
+    app.world.get_mut::<Gravity>(controller).unwrap().up_vector = Vec3::X;
+    run(&mut app, 1);
+    let up_vector = app.world.get::<Gravity>(controller).unwrap().up_vector;
+    // 180 degrees in 0.5 seconds is 6 degrees per tick.
+    assert!(
+        (up_vector.angle_between(Vec3::Y).to_degrees() - 6.0).abs() < 0.1,
+        "{up_vector}"
+    );
+
+    run(&mut app, 7);
+    let halfway = app.world.get::<Gravity>(controller).unwrap().up_vector;
+    let angle = halfway.angle_between(Vec3::Y).to_degrees();
+    assert!((angle - 45.0).abs() < 5.0, "{angle}");
+    // Forward stays perpendicular to up, and doesn't spin around it.
+    let forward = app
+        .world
+        .get::<Upright>(controller)
+        .unwrap()
+        .forward_vector
+        .unwrap();
+    assert!(forward.dot(halfway).abs() < 0.01);
+    assert!(forward.abs_diff_eq(Vec3::NEG_Z, 0.01), "{forward}");
+
+    run(&mut app, 10);
+    let reorientation = app.world.get::<UpReorientation>(controller).unwrap();
+    assert!(!reorientation.reorienting());
+    assert_eq!(
+        app.world.get::<Gravity>(controller).unwrap().up_vector,
+        Vec3::X
+    );
+
+    // Once settled, gravity is left alone so change detection stays quiet.
+    let last_changed = |app: &App| {
+        app.world
+            .entity(controller)
+            .get_ref::<Gravity>()
+            .unwrap()
+            .last_changed()
+    };
+    let settled = last_changed(&app);
+    run(&mut app, 5);
+    assert_eq!(last_changed(&app), settled);
+}
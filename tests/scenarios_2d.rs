@@ -0,0 +1,143 @@
+//! Controller scenarios simulated headlessly with 2D Rapier.
+//!
+//! Run with `cargo test --test scenarios_2d --no-default-features --features rapier2d`.
+
+#![cfg(feature = "rapier2d")]
+
+use bevy::{
+    asset::AssetPlugin, gizmos::GizmoPlugin, prelude::*, render::render_resource::Shader,
+    scene::ScenePlugin, time::TimeUpdateStrategy,
+};
+use bevy_mod_wanderlust::*;
+use bevy_rapier2d::prelude::*;
+use std::time::Duration;
+
+const DT: f32 = 1.0 / 60.0;
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+        ScenePlugin,
+    ))
+    .add_asset::<Shader>()
+    .add_asset::<Mesh>()
+    .add_plugins((
+        GizmoPlugin,
+        RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(1.0),
+        WanderlustPlugin::<Rapier2dBackend>::default(),
+    ))
+    .insert_resource(RapierConfiguration {
+        timestep_mode: TimestepMode::Fixed {
+            dt: DT,
+            substeps: 1,
+        },
+        ..default()
+    })
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        DT,
+    )));
+    app
+}
+
+fn spawn_controller(app: &mut App, translation: Vec3) -> Entity {
+    app.world
+        .spawn((
+            Controller::default(),
+            ControllerInput::default(),
+            ControllerPhysicsBundle::default(),
+            Rapier2dPhysicsBundle {
+                locked_axes: LockedAxes::ROTATION_LOCKED,
+                ..default()
+            },
+            TransformBundle::from_transform(Transform::from_translation(translation)),
+        ))
+        .id()
+}
+
+fn flat_ground(app: &mut App) -> Entity {
+    app.world
+        .spawn((
+            Collider::cuboid(25.0, 0.5),
+            TransformBundle::from_transform(Transform::from_xyz(0.0, -0.5, 0.0)),
+        ))
+        .id()
+}
+
+fn run(app: &mut App, ticks: usize) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+fn translation(app: &App, entity: Entity) -> Vec3 {
+    app.world.get::<Transform>(entity).unwrap().translation
+}
+
+fn grounded(app: &App, entity: Entity) -> bool {
+    **app.world.get::<Grounded>(entity).unwrap()
+}
+
+#[test]
+fn settles_on_ground() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.5, 0.0));
+
+    run(&mut app, 180);
+
+    let float = app.world.get::<Float>(controller).unwrap();
+    let height = translation(&app, controller).y;
+    assert!(
+        height > 0.0 && height < float.distance + 0.1,
+        "rest height {height} should be near float distance {}",
+        float.distance
+    );
+    assert!(grounded(&app, controller));
+}
+
+#[test]
+fn walks_along_ground() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    run(&mut app, 120);
+    let start = translation(&app, controller);
+
+    app.world
+        .get_mut::<ControllerInput>(controller)
+        .unwrap()
+        .movement = Vec3::X;
+    run(&mut app, 60);
+
+    let end = translation(&app, controller);
+    assert!(end.x > start.x + 3.0, "only moved from {start} to {end}");
+    assert!((end.y - start.y).abs() < 0.1);
+    assert!(grounded(&app, controller));
+}
+
+#[test]
+fn jump_leaves_ground_and_lands() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.0, 0.0));
+    run(&mut app, 120);
+    let rest = translation(&app, controller).y;
+
+    app.world
+        .get_mut::<ControllerInput>(controller)
+        .unwrap()
+        .jumping = true;
+    let mut apex = rest;
+    for _ in 0..240 {
+        app.update();
+        apex = apex.max(translation(&app, controller).y);
+    }
+
+    assert!(apex > rest + 0.2, "apex {apex} barely above rest {rest}");
+    assert!((translation(&app, controller).y - rest).abs() < 0.1);
+    assert!(grounded(&app, controller));
+}
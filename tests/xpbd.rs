@@ -0,0 +1,112 @@
+//! Controller behavior on the bevy_xpbd backend.
+//!
+//! Run with `cargo test --test xpbd --no-default-features --features xpbd`.
+
+#![cfg(feature = "xpbd")]
+
+use bevy::{
+    asset::AssetPlugin, gizmos::GizmoPlugin, prelude::*, render::render_resource::Shader,
+    time::TimeUpdateStrategy,
+};
+use bevy_mod_wanderlust::*;
+use bevy_xpbd_3d::prelude::*;
+use std::time::Duration;
+
+const DT: f32 = 1.0 / 60.0;
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .add_asset::<Shader>()
+        .add_plugins((
+            GizmoPlugin,
+            PhysicsPlugins,
+            WanderlustPlugin::<XpbdBackend>::default(),
+        ))
+        .insert_resource(PhysicsTimestep::Fixed(DT))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            DT,
+        )));
+    app
+}
+
+fn spawn_controller(app: &mut App, translation: Vec3) -> Entity {
+    let transform = Transform::from_translation(translation);
+    app.world
+        .spawn((
+            Controller::default(),
+            ControllerInput::default(),
+            ControllerPhysicsBundle::default(),
+            XpbdPhysicsBundle::default(),
+            TransformBundle {
+                local: transform,
+                global: transform.into(),
+            },
+        ))
+        .id()
+}
+
+fn flat_ground(app: &mut App) -> Entity {
+    let transform = Transform::from_xyz(0.0, -0.5, 0.0);
+    app.world
+        .spawn((
+            RigidBody::Static,
+            Collider::cuboid(40.0, 1.0, 40.0),
+            TransformBundle {
+                local: transform,
+                global: transform.into(),
+            },
+        ))
+        .id()
+}
+
+fn run(app: &mut App, ticks: usize) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+fn translation(app: &App, entity: Entity) -> Vec3 {
+    app.world.get::<Transform>(entity).unwrap().translation
+}
+
+#[test]
+fn settles_on_ground() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 1.5, 0.0));
+
+    run(&mut app, 300);
+
+    let float = app.world.get::<Float>(controller).unwrap();
+    let height = translation(&app, controller).y;
+    assert!(
+        height > 0.0 && height < float.distance + 0.1,
+        "rest height {height} should be near float distance {}",
+        float.distance
+    );
+    assert!(**app.world.get::<Grounded>(controller).unwrap());
+}
+
+#[test]
+fn jump_leaves_ground_and_lands() {
+    let mut app = app();
+    flat_ground(&mut app);
+    let controller = spawn_controller(&mut app, Vec3::new(0.0, 0.6, 0.0));
+    run(&mut app, 120);
+    let rest = translation(&app, controller).y;
+
+    app.world
+        .get_mut::<ControllerInput>(controller)
+        .unwrap()
+        .jumping = true;
+    let mut apex = rest;
+    for _ in 0..240 {
+        app.update();
+        apex = apex.max(translation(&app, controller).y);
+    }
+
+    assert!(apex > rest + 0.2, "apex {apex} barely above rest {rest}");
+    assert!((translation(&app, controller).y - rest).abs() < 0.1);
+    assert!(**app.world.get::<Grounded>(controller).unwrap());
+}